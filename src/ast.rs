@@ -17,6 +17,7 @@ pub type UntypedModule = Module<(), UntypedExpr, ()>;
 #[derive(Debug, Clone, PartialEq)]
 pub struct Module<T, Expr, Info> {
     pub name: Vec<String>,
+    pub documentation: Vec<String>,
     pub type_info: Info,
     pub statements: Vec<Statement<T, Expr>>,
 }
@@ -75,11 +76,22 @@ pub enum ArgNames {
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct RecordConstructor {
+    pub doc: Option<String>,
     pub location: SrcSpan,
     pub name: String,
     pub args: Vec<(Option<String>, TypeAst)>,
 }
 
+impl RecordConstructor {
+    pub fn put_doc<'a>(&mut self, new_doc: impl Iterator<Item = &'a str>) {
+        let mut new_doc = new_doc.peekable();
+        if new_doc.peek().is_none() {
+            return;
+        }
+        self.doc = Some(new_doc.join("\n"));
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum TypeAst {
     Constructor {
@@ -251,6 +263,14 @@ pub enum BinOp {
     DivInt,
     DivFloat,
     ModuloInt,
+    Concatenate,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnaryOp {
+    NegateInt,
+    NegateFloat,
+    Not,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -360,10 +380,48 @@ pub enum ClauseGuard<Type> {
         name: String,
     },
 
+    TupleIndex {
+        location: SrcSpan,
+        index: u64,
+        typ: Type,
+        tuple: Box<Self>,
+    },
+
+    FieldAccess {
+        location: SrcSpan,
+        index: Option<u64>,
+        label: String,
+        typ: Type,
+        container: Box<Self>,
+    },
+
     Int {
         location: SrcSpan,
         value: String,
     },
+
+    Float {
+        location: SrcSpan,
+        value: String,
+    },
+
+    String {
+        location: SrcSpan,
+        value: String,
+    },
+
+    Tuple {
+        location: SrcSpan,
+        elems: Vec<Self>,
+    },
+
+    Constructor {
+        location: SrcSpan,
+        module: Option<String>,
+        name: String,
+        args: Vec<CallArg<Self>>,
+        typ: Type,
+    },
 }
 
 impl<A> ClauseGuard<A> {
@@ -383,6 +441,12 @@ impl<A> ClauseGuard<A> {
             ClauseGuard::LtFloat { location, .. } => location,
             ClauseGuard::LtEqFloat { location, .. } => location,
             ClauseGuard::Int { location, .. } => location,
+            ClauseGuard::Float { location, .. } => location,
+            ClauseGuard::String { location, .. } => location,
+            ClauseGuard::Tuple { location, .. } => location,
+            ClauseGuard::Constructor { location, .. } => location,
+            ClauseGuard::TupleIndex { location, .. } => location,
+            ClauseGuard::FieldAccess { location, .. } => location,
         }
     }
 }
@@ -392,6 +456,12 @@ impl TypedClauseGuard {
         match self {
             ClauseGuard::Var { typ, .. } => typ.clone(),
             ClauseGuard::Int { .. } => typ::int(),
+            ClauseGuard::Float { .. } => typ::float(),
+            ClauseGuard::String { .. } => typ::string(),
+            ClauseGuard::Tuple { elems, .. } => typ::tuple(elems.iter().map(|e| e.typ()).collect()),
+            ClauseGuard::Constructor { typ, .. } => typ.clone(),
+            ClauseGuard::TupleIndex { typ, .. } => typ.clone(),
+            ClauseGuard::FieldAccess { typ, .. } => typ.clone(),
             _ => typ::bool(),
         }
     }
@@ -423,6 +493,12 @@ pub enum Pattern<Constructor> {
         value: String,
     },
 
+    StringPrefix {
+        location: SrcSpan,
+        prefix: String,
+        rest: Box<Self>,
+    },
+
     Var {
         location: SrcSpan,
         name: String,
@@ -473,6 +549,7 @@ impl<A> Pattern<A> {
             Pattern::Float { location, .. } => location,
             Pattern::Discard { location, .. } => location,
             Pattern::String { location, .. } => location,
+            Pattern::StringPrefix { location, .. } => location,
             Pattern::Tuple { location, .. } => location,
             Pattern::Constructor { location, .. } => location,
         }
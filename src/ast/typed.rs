@@ -69,6 +69,13 @@ pub enum TypedExpr {
         right: Box<Self>,
     },
 
+    UnaryOp {
+        location: SrcSpan,
+        typ: Arc<Type>,
+        name: UnaryOp,
+        value: Box<Self>,
+    },
+
     Pipe {
         location: SrcSpan,
         typ: Arc<Type>,
@@ -81,6 +88,7 @@ pub enum TypedExpr {
         typ: Arc<Type>,
         value: Box<Self>,
         pattern: Pattern<PatternConstructor>,
+        annotation: Option<TypeAst>,
         then: Box<Self>,
         assert: bool,
     },
@@ -144,6 +152,7 @@ impl TypedExpr {
             Self::Pipe { location, .. } => location,
             Self::Float { location, .. } => location,
             Self::BinOp { location, .. } => location,
+            Self::UnaryOp { location, .. } => location,
             Self::Tuple { location, .. } => location,
             Self::String { location, .. } => location,
             Self::TupleIndex { location, .. } => location,
@@ -166,6 +175,7 @@ impl TypedExpr {
             Self::Pipe { typ, .. } => typ.clone(),
             Self::Float { typ, .. } => typ.clone(),
             Self::BinOp { typ, .. } => typ.clone(),
+            Self::UnaryOp { typ, .. } => typ.clone(),
             Self::Tuple { typ, .. } => typ.clone(),
             Self::String { typ, .. } => typ.clone(),
             Self::TupleIndex { typ, .. } => typ.clone(),
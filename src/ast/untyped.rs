@@ -58,6 +58,12 @@ pub enum UntypedExpr {
         right: Box<Self>,
     },
 
+    UnaryOp {
+        location: SrcSpan,
+        name: UnaryOp,
+        value: Box<Self>,
+    },
+
     Pipe {
         location: SrcSpan,
         left: Box<Self>,
@@ -68,6 +74,7 @@ pub enum UntypedExpr {
         location: SrcSpan,
         value: Box<Self>,
         pattern: Pattern<()>,
+        annotation: Option<TypeAst>,
         then: Box<Self>,
         assert: bool,
     },
@@ -116,6 +123,7 @@ impl UntypedExpr {
             Self::Pipe { location, .. } => location,
             Self::Float { location, .. } => location,
             Self::BinOp { location, .. } => location,
+            Self::UnaryOp { location, .. } => location,
             Self::String { location, .. } => location,
             Self::Tuple { location, .. } => location,
             Self::TupleIndex { location, .. } => location,
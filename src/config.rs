@@ -0,0 +1,549 @@
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    ast::SrcSpan,
+    deps::Requirement,
+    error::{Error, FileIOAction, FileKind, GleamExpect},
+};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use strum_macros::{Display, EnumString, EnumVariantNames, IntoStaticStr};
+
+/// Directories of a project that are used by Gleam or rebar3 for something
+/// other than generated Erlang, so cannot be used as an output directory.
+const RESERVED_DIRS: &[&str] = &[
+    "_build",
+    "_checkouts",
+    "doc",
+    "ebin",
+    "include",
+    "priv",
+    "src",
+    "test",
+];
+
+#[derive(Deserialize, Debug, PartialEq)]
+pub struct ProjectConfig {
+    pub name: String,
+    #[serde(default = "default_version")]
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub licences: Vec<String>,
+    #[serde(default)]
+    pub repository: Repository,
+    #[serde(default)]
+    pub links: Vec<Link>,
+    #[serde(default)]
+    pub erlang: ErlangConfig,
+    #[serde(default)]
+    pub docs: DocsConfig,
+    /// Packages this project depends upon. These are either Hex packages
+    /// with a version requirement, e.g. `gleam_stdlib = "~> 0.8"`, or local
+    /// projects, e.g. `my_lib = { path = "../my_lib" }`.
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, Dependency>,
+    /// Directories within the project containing application modules.
+    #[serde(default = "default_src_dirs")]
+    pub src_dirs: Vec<String>,
+    /// Directories within the project containing test modules, which may
+    /// not be imported by application modules.
+    #[serde(default = "default_test_dirs")]
+    pub test_dirs: Vec<String>,
+    /// The directory build output is written to, relative to the project
+    /// root. Defaults to the project root itself.
+    pub out: Option<String>,
+    #[serde(default)]
+    pub profile: ProfilesConfig,
+}
+
+fn default_version() -> String {
+    "1.0.0".to_string()
+}
+
+fn default_src_dirs() -> Vec<String> {
+    vec!["src".to_string()]
+}
+
+fn default_test_dirs() -> Vec<String> {
+    vec!["test".to_string()]
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Repository {
+    GitHub { user: String, repo: String },
+    GitLab { user: String, repo: String },
+    BitBucket { user: String, repo: String },
+    Custom { url: String },
+    None,
+}
+
+impl Default for Repository {
+    fn default() -> Self {
+        Repository::None
+    }
+}
+
+impl Repository {
+    pub fn url(&self) -> Option<String> {
+        match self {
+            Repository::GitHub { user, repo } => {
+                Some(format!("https://github.com/{}/{}", user, repo))
+            }
+            Repository::GitLab { user, repo } => {
+                Some(format!("https://gitlab.com/{}/{}", user, repo))
+            }
+            Repository::BitBucket { user, repo } => {
+                Some(format!("https://bitbucket.org/{}/{}", user, repo))
+            }
+            Repository::Custom { url } => Some(url.clone()),
+            Repository::None => None,
+        }
+    }
+
+    /// The default URL of a source file for `DocsConfig::source_url`. The
+    /// URLs of custom repositories are not known, so they have none.
+    ///
+    pub fn source_url(&self) -> Option<String> {
+        match self {
+            Repository::GitHub { user, repo } => Some(format!(
+                "https://github.com/{}/{}/blob/v{{version}}/{{path}}#L{{line_start}}-L{{line_end}}",
+                user, repo
+            )),
+            Repository::GitLab { user, repo } => Some(format!(
+                "https://gitlab.com/{}/{}/-/blob/v{{version}}/{{path}}#L{{line_start}}-{{line_end}}",
+                user, repo
+            )),
+            Repository::BitBucket { user, repo } => Some(format!(
+                "https://bitbucket.org/{}/{}/src/v{{version}}/{{path}}#lines-{{line_start}}:{{line_end}}",
+                user, repo
+            )),
+            Repository::Custom { .. } | Repository::None => None,
+        }
+    }
+
+    /// The URL of a file or directory of the project, at the tag of the given
+    /// version as with `source_url`.
+    ///
+    pub fn file_url(&self, version: &str, path: &str) -> Option<String> {
+        match self {
+            Repository::GitHub { user, repo } => Some(format!(
+                "https://github.com/{}/{}/blob/v{}/{}",
+                user, repo, version, path
+            )),
+            Repository::GitLab { user, repo } => Some(format!(
+                "https://gitlab.com/{}/{}/-/blob/v{}/{}",
+                user, repo, version, path
+            )),
+            Repository::BitBucket { user, repo } => Some(format!(
+                "https://bitbucket.org/{}/{}/src/v{}/{}",
+                user, repo, version, path
+            )),
+            Repository::Custom { .. } | Repository::None => None,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+#[serde(untagged)]
+pub enum Dependency {
+    Hex(String),
+    Path { path: PathBuf },
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub struct Link {
+    pub title: String,
+    pub href: String,
+}
+
+/// A set of build settings, selected with `gleam build --profile`.
+///
+#[derive(Debug, PartialEq, Clone, Copy, Display, EnumString, EnumVariantNames, IntoStaticStr)]
+#[strum(serialize_all = "kebab_case")]
+pub enum Profile {
+    /// The default profile, which builds test modules as well as application
+    /// modules.
+    Dev,
+    /// Builds only application modules, for releases.
+    Prod,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Default)]
+pub struct ProfilesConfig {
+    #[serde(default)]
+    pub dev: ProfileConfig,
+    #[serde(default)]
+    pub prod: ProfileConfig,
+}
+
+/// Overrides for the settings of a profile, from the `[profile.dev]` and
+/// `[profile.prod]` sections of gleam.toml.
+///
+#[derive(Deserialize, Debug, PartialEq, Default)]
+pub struct ProfileConfig {
+    /// The directory within the project that Erlang is generated into.
+    pub output_dir: Option<String>,
+    /// Whether modules in the test directories are compiled.
+    pub include_tests: Option<bool>,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Default)]
+pub struct ErlangConfig {
+    /// The module that implements the `application` behaviour, if any. It
+    /// is started when the application is started.
+    pub application_start_module: Option<String>,
+    /// OTP applications other than `kernel` and `stdlib` that must be started
+    /// before this one.
+    #[serde(default)]
+    pub extra_applications: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Default)]
+pub struct DocsConfig {
+    /// The URL of a source file, used to link documented items to their
+    /// source. `{version}`, `{path}`, `{line_start}`, and `{line_end}` are
+    /// replaced with the version of the project, the path of the file within
+    /// the project, and the lines of the item. Defaults to the `v{version}`
+    /// tag of the repository if it is on GitHub, GitLab, or BitBucket.
+    pub source_url: Option<String>,
+    /// Extra pages of documentation, such as guides, rendered from markdown
+    /// files in the project and listed after the README.
+    #[serde(default)]
+    pub pages: Vec<DocsPage>,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub struct DocsPage {
+    pub title: String,
+    /// The markdown file of the page, relative to the project root, e.g.
+    /// `docs/guide.md`.
+    pub path: String,
+}
+
+pub fn read_project_config(root: &str) -> Result<ProjectConfig, Error> {
+    let config_path = PathBuf::from(root).join("gleam.toml");
+
+    let toml = std::fs::read_to_string(&config_path).map_err(|e| Error::FileIO {
+        action: FileIOAction::Read,
+        kind: FileKind::File,
+        path: config_path.clone(),
+        err: Some(e.to_string()),
+    })?;
+
+    parse(&toml, &config_path)
+}
+
+/// Parses and validates the contents of a `gleam.toml` file. Errors point at
+/// the offending location within the file.
+///
+pub fn parse(src: &str, path: &Path) -> Result<ProjectConfig, Error> {
+    let invalid = |location, message| Error::InvalidProjectConfig {
+        path: path.to_path_buf(),
+        src: src.to_string(),
+        location,
+        message,
+    };
+
+    let config: ProjectConfig = toml::from_str(src).map_err(|e| {
+        let location = match e.line_col() {
+            Some((line, column)) => {
+                let start = line_col_offset(src, line, column);
+                SrcSpan {
+                    start,
+                    end: start + 1,
+                }
+            }
+            None => SrcSpan { start: 0, end: 0 },
+        };
+        invalid(location, e.to_string())
+    })?;
+
+    config
+        .validate()
+        .map_err(|(key, message)| invalid(key_location(src, &key), message))?;
+
+    Ok(config)
+}
+
+impl ProjectConfig {
+    fn validate(&self) -> Result<(), (Vec<&str>, String)> {
+        lazy_static! {
+            static ref NAME: regex::Regex =
+                regex::Regex::new("^[a-z][a-z0-9_]*$").gleam_expect("config name regex");
+            static ref VERSION: regex::Regex =
+                regex::Regex::new(r"^\d+\.\d+\.\d+(-[0-9A-Za-z.-]+)?(\+[0-9A-Za-z.-]+)?$")
+                    .gleam_expect("config version regex");
+            static ref DIR: regex::Regex =
+                regex::Regex::new("^[A-Za-z0-9_.-]+$").gleam_expect("config dir regex");
+        }
+
+        if !NAME.is_match(&self.name) {
+            return Err((
+                vec!["name"],
+                "The name may only contain lowercase letters, numbers, and underscores,
+and must start with a letter."
+                    .to_string(),
+            ));
+        }
+
+        if !VERSION.is_match(&self.version) {
+            return Err((
+                vec!["version"],
+                format!(
+                    "`{}` is not a valid version. Versions must be in the form
+MAJOR.MINOR.PATCH, e.g. 1.0.0",
+                    self.version
+                ),
+            ));
+        }
+
+        if self.licences.iter().any(|l| l.trim().is_empty()) {
+            return Err((vec!["licences"], "Licences cannot be blank.".to_string()));
+        }
+
+        if let Some(link) = self
+            .links
+            .iter()
+            .find(|l| !l.href.starts_with("https://") && !l.href.starts_with("http://"))
+        {
+            return Err((
+                vec!["links"],
+                format!(
+                    "The link `{}` has the href `{}`, which is not an http or https URL.",
+                    link.title, link.href
+                ),
+            ));
+        }
+
+        if let Some(url) = &self.docs.source_url {
+            if !url.starts_with("https://") && !url.starts_with("http://") {
+                return Err((
+                    vec!["docs", "source_url"],
+                    format!("The source URL `{}` is not an http or https URL.", url),
+                ));
+            }
+        }
+
+        if let Some(page) = self.docs.pages.iter().find(|page| {
+            !page.path.ends_with(".md")
+                || page
+                    .path
+                    .split('/')
+                    .any(|d| !DIR.is_match(d) || d == "." || d == "..")
+        }) {
+            return Err((
+                vec!["docs", "pages"],
+                format!(
+                    "The page `{}` has the path `{}`, which is not a markdown file within
+the project, e.g. `docs/guide.md`.",
+                    page.title, page.path
+                ),
+            ));
+        }
+
+        if let Some(module) = &self.erlang.application_start_module {
+            if !NAME.is_match(module) {
+                return Err((
+                    vec!["erlang", "application_start_module"],
+                    format!("`{}` is not a valid Erlang module name.", module),
+                ));
+            }
+        }
+
+        for (key, dirs) in [("src_dirs", &self.src_dirs), ("test_dirs", &self.test_dirs)].iter() {
+            if let Some(dir) = dirs
+                .iter()
+                .find(|d| !DIR.is_match(d) || *d == "." || *d == "..")
+            {
+                return Err((
+                    vec![key],
+                    format!(
+                        "`{}` is not a valid directory. Source and test directories must be
+directly within the project, e.g. `src_gen`.",
+                        dir
+                    ),
+                ));
+            }
+        }
+
+        if let Some(dir) = self.test_dirs.iter().find(|d| self.src_dirs.contains(d)) {
+            return Err((
+                vec!["test_dirs"],
+                format!(
+                    "`{}` cannot be both a source directory and a test directory.",
+                    dir
+                ),
+            ));
+        }
+
+        for profile in [Profile::Dev, Profile::Prod].iter().copied() {
+            let dir = self.output_dir(profile);
+            if !DIR.is_match(dir) || dir.starts_with('.') {
+                return Err((
+                    vec!["profile", profile.into(), "output_dir"],
+                    format!(
+                        "`{}` is not a valid directory. The Erlang output directory
+must be a single directory name, e.g. `gen`.",
+                        dir
+                    ),
+                ));
+            }
+            if RESERVED_DIRS.contains(&dir) {
+                return Err((
+                    vec!["profile", profile.into(), "output_dir"],
+                    format!(
+                        "`{}` is used by Gleam or rebar3 so it cannot be the output directory.",
+                        dir
+                    ),
+                ));
+            }
+            if self
+                .src_dirs
+                .iter()
+                .chain(self.test_dirs.iter())
+                .any(|d| d == dir)
+            {
+                return Err((
+                    vec!["profile", profile.into(), "output_dir"],
+                    format!(
+                        "`{}` is a source directory so it cannot be the output directory.",
+                        dir
+                    ),
+                ));
+            }
+        }
+
+        for (name, dependency) in self.dependencies.iter() {
+            if let Dependency::Hex(requirement) = dependency {
+                if let Err(message) = Requirement::parse(requirement) {
+                    return Err((vec!["dependencies", name], message));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn profile_config(&self, profile: Profile) -> &ProfileConfig {
+        match profile {
+            Profile::Dev => &self.profile.dev,
+            Profile::Prod => &self.profile.prod,
+        }
+    }
+
+    /// The directory within the output directory that Erlang is generated
+    /// into when building with the given profile.
+    pub fn output_dir(&self, profile: Profile) -> &str {
+        match (&self.profile_config(profile).output_dir, profile) {
+            (Some(dir), _) => dir,
+            (None, Profile::Dev) => "gen",
+            (None, Profile::Prod) => "gen_prod",
+        }
+    }
+
+    /// Whether test modules are compiled when building with the given
+    /// profile.
+    pub fn include_tests(&self, profile: Profile) -> bool {
+        self.profile_config(profile)
+            .include_tests
+            .unwrap_or(profile == Profile::Dev)
+    }
+
+    /// The parsed version requirements of the project's Hex dependencies.
+    pub fn dependency_requirements(&self) -> BTreeMap<String, Requirement> {
+        self.dependencies
+            .iter()
+            .filter_map(|(name, dependency)| match dependency {
+                Dependency::Hex(requirement) => {
+                    let requirement = Requirement::parse(requirement)
+                        .gleam_expect("validated dependency requirement");
+                    Some((name.clone(), requirement))
+                }
+                Dependency::Path { .. } => None,
+            })
+            .collect()
+    }
+
+    /// The URL of a source file in the repository, with placeholders for
+    /// the path and lines. See `DocsConfig::source_url`.
+    pub fn source_url(&self) -> Option<String> {
+        self.docs
+            .source_url
+            .clone()
+            .or_else(|| self.repository.source_url())
+    }
+
+    /// The directories of the project's path dependencies, relative to the
+    /// project root.
+    pub fn path_dependencies(&self) -> impl Iterator<Item = (&String, &PathBuf)> {
+        self.dependencies
+            .iter()
+            .filter_map(|(name, dependency)| match dependency {
+                Dependency::Path { path } => Some((name, path)),
+                Dependency::Hex(_) => None,
+            })
+    }
+}
+
+fn line_col_offset(src: &str, line: usize, column: usize) -> usize {
+    let line_start: usize = src.split('\n').take(line).map(|l| l.len() + 1).sum();
+    (line_start + column).min(src.len())
+}
+
+/// Finds the span of the given key within the TOML source so that errors can
+/// point at it. Keys may be defined within a table (`[erlang]`), as an array
+/// of tables (`[[links]]`), or with a dotted name (`erlang.key = ...`). If the
+/// key cannot be found the start of the file is used.
+///
+fn key_location(src: &str, key: &[&str]) -> SrcSpan {
+    let mut table: Vec<String> = vec![];
+    let mut offset = 0;
+
+    for line in src.split('\n') {
+        let line_start = offset;
+        offset += line.len() + 1;
+
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+        let start = line_start + indent;
+
+        if trimmed.starts_with('[') {
+            let header = trimmed.trim_start_matches('[');
+            let header = header.split(']').next().unwrap_or("");
+            table = split_key(header);
+            if table == key {
+                return SrcSpan {
+                    start,
+                    end: start + trimmed.trim_end().len(),
+                };
+            }
+            continue;
+        }
+
+        let name = match trimmed.find('=') {
+            Some(i) => &trimmed[..i],
+            None => continue,
+        };
+        let mut path = table.clone();
+        path.extend(split_key(name));
+        if path == key {
+            return SrcSpan {
+                start,
+                end: start + name.trim_end().len(),
+            };
+        }
+    }
+
+    SrcSpan { start: 0, end: 0 }
+}
+
+fn split_key(key: &str) -> Vec<String> {
+    key.split('.')
+        .map(|k| k.trim().trim_matches('"').to_string())
+        .collect()
+}
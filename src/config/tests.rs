@@ -0,0 +1,301 @@
+use super::*;
+
+#[test]
+fn parse_test() {
+    macro_rules! assert_config {
+        ($src:expr, $config:expr $(,)?) => {
+            let path = PathBuf::from("/gleam.toml");
+            assert_eq!(Ok($config), parse($src, &path));
+        };
+    }
+
+    macro_rules! assert_invalid {
+        ($src:expr, $location:expr $(,)?) => {
+            let path = PathBuf::from("/gleam.toml");
+            match parse($src, &path) {
+                Err(Error::InvalidProjectConfig { location, .. }) => {
+                    assert_eq!(($src, $location), ($src, location))
+                }
+                other => panic!("expected an invalid config error, got {:?}", other),
+            }
+        };
+    }
+
+    assert_config!(
+        r#"name = "wibble""#,
+        ProjectConfig {
+            name: "wibble".to_string(),
+            version: "1.0.0".to_string(),
+            description: "".to_string(),
+            licences: vec![],
+            repository: Repository::None,
+            links: vec![],
+            erlang: ErlangConfig::default(),
+            docs: DocsConfig::default(),
+            dependencies: BTreeMap::new(),
+            src_dirs: vec!["src".to_string()],
+            test_dirs: vec!["test".to_string()],
+            out: None,
+            profile: ProfilesConfig::default(),
+        }
+    );
+
+    assert_config!(
+        r#"
+name = "wibble"
+version = "1.2.3-rc1"
+description = "A wobbly thing"
+licences = ["Apache-2.0", "MIT"]
+src_dirs = ["src", "src_gen"]
+test_dirs = ["test", "itest"]
+out = "_build/gleam"
+
+[repository]
+type = "github"
+user = "someone"
+repo = "wibble"
+
+[[links]]
+title = "Website"
+href = "https://wibble.example.com"
+
+[profile.prod]
+output_dir = "release"
+include_tests = true
+
+[erlang]
+application_start_module = "wibble_app"
+extra_applications = ["inets", "ssl"]
+
+[dependencies]
+gleam_stdlib = "~> 0.8"
+my_lib = { path = "../my_lib" }
+"#,
+        ProjectConfig {
+            name: "wibble".to_string(),
+            version: "1.2.3-rc1".to_string(),
+            description: "A wobbly thing".to_string(),
+            licences: vec!["Apache-2.0".to_string(), "MIT".to_string()],
+            repository: Repository::GitHub {
+                user: "someone".to_string(),
+                repo: "wibble".to_string(),
+            },
+            links: vec![Link {
+                title: "Website".to_string(),
+                href: "https://wibble.example.com".to_string(),
+            }],
+            erlang: ErlangConfig {
+                application_start_module: Some("wibble_app".to_string()),
+                extra_applications: vec!["inets".to_string(), "ssl".to_string()],
+            },
+            docs: DocsConfig::default(),
+            dependencies: vec![
+                (
+                    "gleam_stdlib".to_string(),
+                    Dependency::Hex("~> 0.8".to_string())
+                ),
+                (
+                    "my_lib".to_string(),
+                    Dependency::Path {
+                        path: PathBuf::from("../my_lib")
+                    }
+                ),
+            ]
+            .into_iter()
+            .collect(),
+            src_dirs: vec!["src".to_string(), "src_gen".to_string()],
+            test_dirs: vec!["test".to_string(), "itest".to_string()],
+            out: Some("_build/gleam".to_string()),
+            profile: ProfilesConfig {
+                dev: ProfileConfig::default(),
+                prod: ProfileConfig {
+                    output_dir: Some("release".to_string()),
+                    include_tests: Some(true),
+                },
+            },
+        }
+    );
+
+    // Syntax and schema errors point at the location reported by the parser
+    assert_invalid!("name = wibble", SrcSpan { start: 7, end: 8 });
+    assert_invalid!(
+        "name = \"wibble\"\nversion = 1\n",
+        SrcSpan { start: 26, end: 27 }
+    );
+
+    // Validation errors point at the offending key
+    assert_invalid!("name = \"Wibble\"", SrcSpan { start: 0, end: 4 });
+    assert_invalid!(
+        "name = \"wibble\"\nversion = \"one\"\n",
+        SrcSpan { start: 16, end: 23 }
+    );
+    assert_invalid!(
+        "name = \"wibble\"\n\n[[links]]\ntitle = \"Home\"\nhref = \"wibble.com\"\n",
+        SrcSpan { start: 17, end: 26 }
+    );
+    assert_invalid!(
+        "name = \"wibble\"\n\n[erlang]\n  application_start_module = \"Wibble\"\n",
+        SrcSpan { start: 28, end: 52 }
+    );
+    assert_invalid!(
+        "name = \"wibble\"\nerlang.application_start_module = \"Wibble\"\n",
+        SrcSpan { start: 16, end: 47 }
+    );
+    assert_invalid!(
+        "name = \"wibble\"\n\n[[docs.pages]]\ntitle = \"Guide\"\npath = \"../guide.md\"\n",
+        SrcSpan { start: 17, end: 31 }
+    );
+    assert_invalid!(
+        "name = \"wibble\"\n\n[[docs.pages]]\ntitle = \"Guide\"\npath = \"docs/guide.html\"\n",
+        SrcSpan { start: 17, end: 31 }
+    );
+    assert_invalid!(
+        "name = \"wibble\"\n[docs]\nsource_url = \"src/{path}\"\n",
+        SrcSpan { start: 23, end: 33 }
+    );
+    assert_invalid!(
+        "name = \"wibble\"\n[dependencies]\ngleam_stdlib = \"~> 1\"\n",
+        SrcSpan { start: 31, end: 43 }
+    );
+    assert_invalid!(
+        "name = \"wibble\"\nsrc_dirs = [\"../elsewhere\"]\n",
+        SrcSpan { start: 16, end: 24 }
+    );
+    assert_invalid!(
+        "name = \"wibble\"\nsrc_dirs = [\"src\"]\ntest_dirs = [\"src\"]\n",
+        SrcSpan { start: 35, end: 44 }
+    );
+    assert_invalid!(
+        "name = \"wibble\"\n[profile.dev]\noutput_dir = \"src\"\n",
+        SrcSpan { start: 30, end: 40 }
+    );
+    assert_invalid!(
+        "name = \"wibble\"\n[profile.dev]\noutput_dir = \".git\"\n",
+        SrcSpan { start: 30, end: 40 }
+    );
+    assert_invalid!(
+        "name = \"wibble\"\n[profile.prod]\noutput_dir = \"_build\"\n",
+        SrcSpan { start: 31, end: 41 }
+    );
+    assert_invalid!(
+        "name = \"wibble\"\n[profile.dev]\noutput_dir = \"priv\"\n",
+        SrcSpan { start: 30, end: 40 }
+    );
+}
+
+#[test]
+fn profile_test() {
+    let path = PathBuf::from("/gleam.toml");
+    let config = parse("name = \"wibble\"", &path).unwrap();
+    assert_eq!("gen", config.output_dir(Profile::Dev));
+    assert_eq!("gen_prod", config.output_dir(Profile::Prod));
+    assert!(config.include_tests(Profile::Dev));
+    assert!(!config.include_tests(Profile::Prod));
+
+    let config = parse(
+        "name = \"wibble\"\n[profile.prod]\noutput_dir = \"release\"\ninclude_tests = true\n",
+        &path,
+    )
+    .unwrap();
+    assert_eq!("release", config.output_dir(Profile::Prod));
+    assert!(config.include_tests(Profile::Prod));
+}
+
+#[test]
+fn repository_url_test() {
+    let user = "someone".to_string();
+    let repo = "wibble".to_string();
+    assert_eq!(
+        Some("https://github.com/someone/wibble".to_string()),
+        Repository::GitHub {
+            user: user.clone(),
+            repo: repo.clone()
+        }
+        .url()
+    );
+    assert_eq!(
+        Some("https://gitlab.com/someone/wibble".to_string()),
+        Repository::GitLab {
+            user: user.clone(),
+            repo: repo.clone()
+        }
+        .url()
+    );
+    assert_eq!(
+        Some("https://bitbucket.org/someone/wibble".to_string()),
+        Repository::BitBucket { user, repo }.url()
+    );
+    assert_eq!(None, Repository::None.url());
+}
+
+#[test]
+fn repository_file_url_test() {
+    let user = "someone".to_string();
+    let repo = "wibble".to_string();
+    assert_eq!(
+        Some("https://github.com/someone/wibble/blob/v1.2.0/docs/guide.md".to_string()),
+        Repository::GitHub {
+            user: user.clone(),
+            repo: repo.clone()
+        }
+        .file_url("1.2.0", "docs/guide.md")
+    );
+    assert_eq!(
+        Some("https://gitlab.com/someone/wibble/-/blob/v1.2.0/LICENSE".to_string()),
+        Repository::GitLab {
+            user: user.clone(),
+            repo: repo.clone()
+        }
+        .file_url("1.2.0", "LICENSE")
+    );
+    assert_eq!(
+        Some("https://bitbucket.org/someone/wibble/src/v1.2.0/LICENSE".to_string()),
+        Repository::BitBucket { user, repo }.file_url("1.2.0", "LICENSE")
+    );
+    assert_eq!(
+        None,
+        Repository::Custom {
+            url: "https://git.example.com/wibble".to_string()
+        }
+        .file_url("1.2.0", "LICENSE")
+    );
+}
+
+#[test]
+fn source_url_test() {
+    let path = PathBuf::from("/gleam.toml");
+    let source_url = |toml: &str| parse(toml, &path).unwrap().source_url();
+
+    assert_eq!(
+        Some(
+            "https://github.com/someone/wibble/blob/v{version}/{path}#L{line_start}-L{line_end}"
+                .to_string()
+        ),
+        source_url(
+            "name = \"wibble\"\n[repository]\ntype = \"github\"\nuser = \"someone\"\nrepo = \"wibble\"\n"
+        )
+    );
+    assert_eq!(
+        Some(
+            "https://gitlab.com/someone/wibble/-/blob/v{version}/{path}#L{line_start}-{line_end}"
+                .to_string()
+        ),
+        source_url(
+            "name = \"wibble\"\n[repository]\ntype = \"gitlab\"\nuser = \"someone\"\nrepo = \"wibble\"\n"
+        )
+    );
+    assert_eq!(
+        None,
+        source_url(
+            "name = \"wibble\"\n[repository]\ntype = \"custom\"\nurl = \"https://git.example.com/wibble\"\n"
+        )
+    );
+
+    // An explicit source URL takes precedence over the repository
+    assert_eq!(
+        Some("https://git.example.com/wibble/src/tag/v{version}/{path}#L{line_start}".to_string()),
+        source_url(
+            "name = \"wibble\"\n[repository]\ntype = \"github\"\nuser = \"someone\"\nrepo = \"wibble\"\n[docs]\nsource_url = \"https://git.example.com/wibble/src/tag/v{version}/{path}#L{line_start}\"\n"
+        )
+    );
+}
@@ -0,0 +1,503 @@
+mod fetch;
+mod hex;
+pub mod manifest;
+mod path;
+#[cfg(test)]
+mod tests;
+
+pub use fetch::{checksum, package_dir};
+pub use hex::HexIndex;
+pub use path::{path_packages, PathPackage};
+
+use crate::config::ProjectConfig;
+use crate::error::{Error, FileIOAction, FileKind};
+use manifest::Manifest;
+use semver::Version;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+/// A source of information about published packages, such as the Hex
+/// package repository.
+///
+pub trait PackageIndex {
+    /// All the published versions of a package, in any order.
+    fn versions(&self, package: &str) -> Result<Vec<Version>, Error>;
+
+    /// The dependencies of a specific version of a package.
+    fn dependencies(
+        &self,
+        package: &str,
+        version: &Version,
+    ) -> Result<BTreeMap<String, Requirement>, Error>;
+
+    /// The Hex tarball of a specific version of a package.
+    fn download(&self, package: &str, version: &Version) -> Result<Vec<u8>, Error>;
+}
+
+/// The index used when building. This is Hex unless the
+/// `GLEAM_PACKAGE_INDEX` environment variable names a directory to be used as
+/// a `LocalIndex` instead.
+///
+pub fn default_index() -> Box<dyn PackageIndex> {
+    match std::env::var_os("GLEAM_PACKAGE_INDEX") {
+        Some(dir) => Box::new(LocalIndex::new(PathBuf::from(dir))),
+        None => Box::new(HexIndex),
+    }
+}
+
+/// A package index read from a local directory, used in place of Hex for
+/// testing and offline work. Each package is described by a
+/// `<package>.toml` file in the directory:
+///
+/// ```toml
+/// [[releases]]
+/// version = "1.0.0"
+///
+/// [releases.dependencies]
+/// gleam_stdlib = "~> 0.8"
+/// ```
+///
+/// The tarball of each release is stored alongside as
+/// `<package>-<version>.tar`.
+///
+#[derive(Debug)]
+pub struct LocalIndex {
+    dir: PathBuf,
+}
+
+#[derive(Deserialize)]
+struct LocalPackage {
+    #[serde(default)]
+    releases: Vec<LocalRelease>,
+}
+
+#[derive(Deserialize)]
+struct LocalRelease {
+    version: String,
+    #[serde(default)]
+    dependencies: BTreeMap<String, String>,
+}
+
+impl LocalIndex {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn releases(
+        &self,
+        package: &str,
+    ) -> Result<Vec<(Version, BTreeMap<String, Requirement>)>, Error> {
+        let path = self.dir.join(format!("{}.toml", package));
+        if !path.is_file() {
+            return Err(Error::UnknownPackage {
+                name: package.to_string(),
+            });
+        }
+
+        let parse_error = |err: String| Error::FileIO {
+            action: FileIOAction::Parse,
+            kind: FileKind::File,
+            path: path.clone(),
+            err: Some(err),
+        };
+
+        let src = std::fs::read_to_string(&path).map_err(|e| Error::FileIO {
+            action: FileIOAction::Read,
+            kind: FileKind::File,
+            path: path.clone(),
+            err: Some(e.to_string()),
+        })?;
+        let local: LocalPackage = toml::from_str(&src).map_err(|e| parse_error(e.to_string()))?;
+
+        local
+            .releases
+            .into_iter()
+            .map(|release| {
+                let version = Version::parse(&release.version)
+                    .map_err(|e| parse_error(format!("{}: {}", release.version, e)))?;
+                let dependencies = release
+                    .dependencies
+                    .into_iter()
+                    .map(|(name, requirement)| {
+                        Requirement::parse(&requirement)
+                            .map(|requirement| (name, requirement))
+                            .map_err(parse_error)
+                    })
+                    .collect::<Result<_, _>>()?;
+                Ok((version, dependencies))
+            })
+            .collect()
+    }
+}
+
+impl PackageIndex for LocalIndex {
+    fn versions(&self, package: &str) -> Result<Vec<Version>, Error> {
+        Ok(self
+            .releases(package)?
+            .into_iter()
+            .map(|(version, _)| version)
+            .collect())
+    }
+
+    fn dependencies(
+        &self,
+        package: &str,
+        version: &Version,
+    ) -> Result<BTreeMap<String, Requirement>, Error> {
+        Ok(self
+            .releases(package)?
+            .into_iter()
+            .find(|(v, _)| v == version)
+            .map(|(_, dependencies)| dependencies)
+            .unwrap_or_default())
+    }
+
+    fn download(&self, package: &str, version: &Version) -> Result<Vec<u8>, Error> {
+        let path = self.dir.join(format!("{}-{}.tar", package, version));
+        std::fs::read(&path).map_err(|e| Error::FileIO {
+            action: FileIOAction::Read,
+            kind: FileKind::File,
+            path,
+            err: Some(e.to_string()),
+        })
+    }
+}
+
+/// A version requirement written using Hex's syntax, e.g. `~> 1.2`,
+/// `>= 1.0.0 and < 2.0.0`, or `== 1.0.0 or == 1.1.0`.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Requirement {
+    source: String,
+    alternatives: Vec<Vec<Comparator>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Comparator {
+    operator: Operator,
+    version: Version,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Operator {
+    Eq,
+    NotEq,
+    Gt,
+    GtEq,
+    Lt,
+    LtEq,
+}
+
+impl Requirement {
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let alternatives = source
+            .split(" or ")
+            .map(|alternative| {
+                let mut comparators = vec![];
+                for clause in alternative.split(" and ") {
+                    comparators.extend(parse_clause(clause.trim(), source)?);
+                }
+                Ok(comparators)
+            })
+            .collect::<Result<_, String>>()?;
+
+        Ok(Self {
+            source: source.trim().to_string(),
+            alternatives,
+        })
+    }
+
+    pub fn matches(&self, version: &Version) -> bool {
+        self.alternatives.iter().any(|comparators| {
+            // Pre-release versions are only selected when asked for explicitly
+            (!version.is_prerelease() || comparators.iter().any(|c| c.version.is_prerelease()))
+                && comparators.iter().all(|c| c.matches(version))
+        })
+    }
+}
+
+impl std::fmt::Display for Requirement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+impl Comparator {
+    fn matches(&self, version: &Version) -> bool {
+        match self.operator {
+            Operator::Eq => version == &self.version,
+            Operator::NotEq => version != &self.version,
+            Operator::Gt => version > &self.version,
+            Operator::GtEq => version >= &self.version,
+            Operator::Lt => version < &self.version,
+            Operator::LtEq => version <= &self.version,
+        }
+    }
+}
+
+fn parse_clause(clause: &str, source: &str) -> Result<Vec<Comparator>, String> {
+    let invalid = || format!("`{}` is not a valid version requirement.", source);
+    let comparator = |operator, version: &str| {
+        Version::parse(version.trim())
+            .map(|version| Comparator { operator, version })
+            .map_err(|_| invalid())
+    };
+
+    if clause.starts_with("~>") {
+        // `~> 1.2` allows any 1.x version from 1.2.0, and `~> 1.2.3` allows
+        // any 1.2.x version from 1.2.3.
+        let version = clause[2..].trim();
+        let core = version.split(|c| c == '-' || c == '+').next().unwrap_or("");
+        let parts = core
+            .split('.')
+            .map(|part| part.parse::<u64>().map_err(|_| invalid()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let (lower, upper) = match parts.as_slice() {
+            [major, minor] => (
+                comparator(Operator::GtEq, &format!("{}.{}.0", major, minor))?,
+                Version::new(major + 1, 0, 0),
+            ),
+            [major, minor, _] => (
+                comparator(Operator::GtEq, version)?,
+                Version::new(*major, minor + 1, 0),
+            ),
+            _ => return Err(invalid()),
+        };
+        return Ok(vec![
+            lower,
+            Comparator {
+                operator: Operator::Lt,
+                version: upper,
+            },
+        ]);
+    }
+
+    let operators = [
+        ("==", Operator::Eq),
+        ("!=", Operator::NotEq),
+        (">=", Operator::GtEq),
+        ("<=", Operator::LtEq),
+        (">", Operator::Gt),
+        ("<", Operator::Lt),
+    ];
+    for (prefix, operator) in operators.iter() {
+        if clause.starts_with(prefix) {
+            return Ok(vec![comparator(*operator, &clause[prefix.len()..])?]);
+        }
+    }
+
+    // A bare version requires exactly that version
+    Ok(vec![comparator(Operator::Eq, clause)?])
+}
+
+/// The Hex dependencies of each local package, that is the project itself and
+/// any packages it depends upon by path, keyed by the name of the package.
+///
+pub type Requirements = BTreeMap<String, BTreeMap<String, Requirement>>;
+
+pub fn requirements(config: &ProjectConfig, path_packages: &[PathPackage]) -> Requirements {
+    let mut requirements = Requirements::new();
+    requirements.insert(config.name.clone(), config.dependency_requirements());
+    for package in path_packages {
+        requirements.insert(
+            package.name.clone(),
+            package.config.dependency_requirements(),
+        );
+    }
+    requirements
+}
+
+/// Makes sure that the project's dependencies have been downloaded, returning
+/// the manifest of the packages used. The versions locked in gleam.lock are
+/// used when they still satisfy the requirements in gleam.toml and no package
+/// has been removed from them. Otherwise, or when updating, the dependencies
+/// are resolved again and gleam.lock is rewritten.
+///
+pub fn download(
+    index: &dyn PackageIndex,
+    root: &Path,
+    requirements: &Requirements,
+    update: bool,
+) -> Result<Manifest, Error> {
+    let locked = Manifest::read(root)?;
+    match &locked {
+        Some(manifest) if !update && manifest.satisfies(requirements) => {
+            fetch::fetch(index, root, &manifest.locked(), Some(manifest))
+        }
+        _ => {
+            let packages = resolve(index, requirements)?;
+            let manifest = fetch::fetch(index, root, &packages, locked.as_ref())?;
+            manifest.write(root)?;
+            Ok(manifest)
+        }
+    }
+}
+
+/// Selects a version of each package in the dependency graph of the local
+/// packages such that every requirement is satisfied, preferring the newest
+/// versions. Returns the selected version of each package, with the names of
+/// the packages it depends upon.
+///
+pub fn resolve(
+    index: &dyn PackageIndex,
+    requirements: &Requirements,
+) -> Result<BTreeMap<String, (Version, Vec<String>)>, Error> {
+    let mut resolver = Resolver {
+        index,
+        versions: HashMap::new(),
+        dependencies: HashMap::new(),
+    };
+
+    let mut state = State::default();
+    for (required_by, dependencies) in requirements {
+        for (name, requirement) in dependencies {
+            state.require(required_by.clone(), name, requirement);
+        }
+    }
+
+    let selected = resolver.solve(state).map_err(|failure| match failure {
+        Failure::Error(error) => error,
+        Failure::Conflict {
+            package,
+            constraints,
+        } => Error::DependencyConflict {
+            package,
+            required_by: constraints
+                .into_iter()
+                .map(|c| (c.required_by, c.requirement.to_string()))
+                .collect(),
+        },
+    })?;
+
+    // The dependencies of each selected version were fetched while solving
+    Ok(selected
+        .into_iter()
+        .map(|(name, version)| {
+            let dependencies = resolver
+                .dependencies
+                .get(&(name.clone(), version.clone()))
+                .map(|dependencies| dependencies.keys().cloned().collect())
+                .unwrap_or_default();
+            (name, (version, dependencies))
+        })
+        .collect())
+}
+
+struct Resolver<'a> {
+    index: &'a dyn PackageIndex,
+    versions: HashMap<String, Vec<Version>>,
+    dependencies: HashMap<(String, Version), BTreeMap<String, Requirement>>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct State {
+    selected: BTreeMap<String, Version>,
+    constraints: BTreeMap<String, Vec<Constraint>>,
+}
+
+#[derive(Debug, Clone)]
+struct Constraint {
+    required_by: String,
+    requirement: Requirement,
+}
+
+#[derive(Debug)]
+enum Failure {
+    Error(Error),
+    Conflict {
+        package: String,
+        constraints: Vec<Constraint>,
+    },
+}
+
+impl State {
+    fn require(&mut self, required_by: String, package: &str, requirement: &Requirement) {
+        self.constraints
+            .entry(package.to_string())
+            .or_insert_with(Vec::new)
+            .push(Constraint {
+                required_by,
+                requirement: requirement.clone(),
+            });
+    }
+}
+
+impl<'a> Resolver<'a> {
+    fn solve(&mut self, state: State) -> Result<BTreeMap<String, Version>, Failure> {
+        let package = match state
+            .constraints
+            .keys()
+            .find(|package| !state.selected.contains_key(*package))
+        {
+            None => return Ok(state.selected),
+            Some(package) => package.clone(),
+        };
+
+        let constraints = state.constraints.get(&package).cloned().unwrap_or_default();
+        let candidates: Vec<Version> = self
+            .versions(&package)?
+            .into_iter()
+            .filter(|version| constraints.iter().all(|c| c.requirement.matches(version)))
+            .collect();
+
+        // If no candidate works the most recent conflict explains why
+        let mut conflict = Failure::Conflict {
+            package: package.clone(),
+            constraints,
+        };
+
+        'candidates: for version in candidates {
+            let mut state = state.clone();
+            let required_by = format!("{} {}", package, version);
+            for (dependency, requirement) in self.dependencies(&package, &version)? {
+                state.require(required_by.clone(), &dependency, &requirement);
+                if let Some(selected) = state.selected.get(&dependency) {
+                    if !requirement.matches(selected) {
+                        conflict = Failure::Conflict {
+                            constraints: state.constraints[&dependency].clone(),
+                            package: dependency,
+                        };
+                        continue 'candidates;
+                    }
+                }
+            }
+            state.selected.insert(package.clone(), version);
+
+            match self.solve(state) {
+                Ok(selected) => return Ok(selected),
+                Err(Failure::Error(error)) => return Err(Failure::Error(error)),
+                Err(failure) => conflict = failure,
+            }
+        }
+
+        Err(conflict)
+    }
+
+    fn versions(&mut self, package: &str) -> Result<Vec<Version>, Failure> {
+        if let Some(versions) = self.versions.get(package) {
+            return Ok(versions.clone());
+        }
+        let mut versions = self.index.versions(package).map_err(Failure::Error)?;
+        versions.sort_by(|a, b| b.cmp(a));
+        self.versions.insert(package.to_string(), versions.clone());
+        Ok(versions)
+    }
+
+    fn dependencies(
+        &mut self,
+        package: &str,
+        version: &Version,
+    ) -> Result<BTreeMap<String, Requirement>, Failure> {
+        let key = (package.to_string(), version.clone());
+        if let Some(dependencies) = self.dependencies.get(&key) {
+            return Ok(dependencies.clone());
+        }
+        let dependencies = self
+            .index
+            .dependencies(package, version)
+            .map_err(Failure::Error)?;
+        self.dependencies.insert(key, dependencies.clone());
+        Ok(dependencies)
+    }
+}
@@ -0,0 +1,154 @@
+use super::manifest::{Manifest, ManifestPackage};
+use super::PackageIndex;
+use crate::error::{Error, FileIOAction, FileKind};
+use semver::Version;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Unpacked packages contain this file, holding the checksum of the tarball
+/// they were unpacked from, so that stale packages are replaced.
+const CHECKSUM_FILE: &str = ".gleam_checksum";
+
+/// Makes sure that the given version of each package is unpacked into
+/// `_build/default/lib`, downloading any tarballs that are not already cached
+/// in `_build/packages`. Checksums are verified against the previous manifest
+/// so that the code of a locked package cannot silently change. Each package
+/// is given with the names of the packages it depends upon, which are
+/// recorded in the manifest.
+///
+pub fn fetch(
+    index: &dyn PackageIndex,
+    root: &Path,
+    packages: &BTreeMap<String, (Version, Vec<String>)>,
+    previous: Option<&Manifest>,
+) -> Result<Manifest, Error> {
+    let mut manifest = Manifest::default();
+
+    for (name, (version, requirements)) in packages {
+        let tarball = fetch_tarball(index, root, name, version)?;
+        let checksum = checksum(&tarball);
+
+        if let Some(expected) = previous.and_then(|m| m.checksum(name, version)) {
+            if expected != checksum {
+                return Err(Error::ChecksumMismatch {
+                    package: name.clone(),
+                    version: version.to_string(),
+                    expected: expected.to_string(),
+                    actual: checksum,
+                });
+            }
+        }
+
+        let dir = package_dir(root, name);
+        let unpacked_checksum = std::fs::read_to_string(dir.join(CHECKSUM_FILE)).ok();
+        if unpacked_checksum.as_deref() != Some(checksum.as_str()) {
+            unpack(&tarball, &dir)?;
+            write(&dir.join(CHECKSUM_FILE), checksum.as_bytes())?;
+        }
+
+        manifest.packages.push(ManifestPackage {
+            name: name.clone(),
+            version: version.to_string(),
+            checksum,
+            requirements: requirements.clone(),
+        });
+    }
+
+    Ok(manifest)
+}
+
+/// The directory a package is unpacked into.
+///
+pub fn package_dir(root: &Path, name: &str) -> PathBuf {
+    root.join("_build").join("default").join("lib").join(name)
+}
+
+fn fetch_tarball(
+    index: &dyn PackageIndex,
+    root: &Path,
+    name: &str,
+    version: &Version,
+) -> Result<Vec<u8>, Error> {
+    let path = root
+        .join("_build")
+        .join("packages")
+        .join(format!("{}-{}.tar", name, version));
+
+    if let Ok(tarball) = std::fs::read(&path) {
+        return Ok(tarball);
+    }
+
+    println!("Downloading {} {}", name, version);
+    let tarball = index.download(name, version)?;
+    create_dir(path.parent().unwrap_or(root))?;
+    write(&path, &tarball)?;
+    Ok(tarball)
+}
+
+pub fn checksum(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{:02X}", byte))
+        .collect()
+}
+
+/// Unpacks the source code of a Hex tarball, which is held in the
+/// `contents.tar.gz` file within it.
+///
+fn unpack(tarball: &[u8], dir: &Path) -> Result<(), Error> {
+    let unpack_error = |err: std::io::Error| Error::FileIO {
+        action: FileIOAction::Create,
+        kind: FileKind::Directory,
+        path: dir.to_path_buf(),
+        err: Some(err.to_string()),
+    };
+
+    if dir.exists() {
+        std::fs::remove_dir_all(dir).map_err(|e| Error::FileIO {
+            action: FileIOAction::Delete,
+            kind: FileKind::Directory,
+            path: dir.to_path_buf(),
+            err: Some(e.to_string()),
+        })?;
+    }
+    create_dir(dir)?;
+
+    let mut archive = tar::Archive::new(tarball);
+    for entry in archive.entries().map_err(unpack_error)? {
+        let entry = entry.map_err(unpack_error)?;
+        let is_contents = entry
+            .path()
+            .map(|path| path == Path::new("contents.tar.gz"))
+            .unwrap_or(false);
+        if is_contents {
+            let contents = flate2::read::GzDecoder::new(entry);
+            return tar::Archive::new(contents)
+                .unpack(dir)
+                .map_err(unpack_error);
+        }
+    }
+
+    Err(unpack_error(std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        "The package tarball has no contents.tar.gz file",
+    )))
+}
+
+fn create_dir(dir: &Path) -> Result<(), Error> {
+    std::fs::create_dir_all(dir).map_err(|e| Error::FileIO {
+        action: FileIOAction::Create,
+        kind: FileKind::Directory,
+        path: dir.to_path_buf(),
+        err: Some(e.to_string()),
+    })
+}
+
+fn write(path: &Path, bytes: &[u8]) -> Result<(), Error> {
+    std::fs::write(path, bytes).map_err(|e| Error::FileIO {
+        action: FileIOAction::WriteTo,
+        kind: FileKind::File,
+        path: path.to_path_buf(),
+        err: Some(e.to_string()),
+    })
+}
@@ -0,0 +1,128 @@
+use super::{PackageIndex, Requirement};
+use crate::error::Error;
+use semver::Version;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+const HEX_API: &str = "https://hex.pm/api";
+const HEX_REPO: &str = "https://repo.hex.pm";
+
+/// The Hex package repository, queried using its HTTP API.
+///
+#[derive(Debug, Default)]
+pub struct HexIndex;
+
+#[derive(Deserialize)]
+struct Package {
+    releases: Vec<PackageRelease>,
+}
+
+#[derive(Deserialize)]
+struct PackageRelease {
+    version: String,
+}
+
+#[derive(Deserialize)]
+struct Release {
+    #[serde(default)]
+    requirements: BTreeMap<String, ReleaseRequirement>,
+}
+
+#[derive(Deserialize)]
+struct ReleaseRequirement {
+    requirement: String,
+    #[serde(default)]
+    optional: bool,
+}
+
+impl HexIndex {
+    fn get<T: serde::de::DeserializeOwned>(&self, package: &str, path: &str) -> Result<T, Error> {
+        let response = self.call(package, &format!("{}/{}", HEX_API, path))?;
+        response
+            .into_json_deserialize()
+            .map_err(|e| Error::PackageIndex {
+                package: package.to_string(),
+                err: e.to_string(),
+            })
+    }
+
+    fn call(&self, package: &str, url: &str) -> Result<ureq::Response, Error> {
+        let index_error = |err: String| Error::PackageIndex {
+            package: package.to_string(),
+            err,
+        };
+
+        let response = ureq::get(url)
+            .set("Accept", "application/json")
+            .set("User-Agent", concat!("gleam/", env!("CARGO_PKG_VERSION")))
+            .call();
+
+        if response.status() == 404 {
+            return Err(Error::UnknownPackage {
+                name: package.to_string(),
+            });
+        }
+        if let Some(err) = response.synthetic_error() {
+            return Err(index_error(err.to_string()));
+        }
+        if !response.ok() {
+            return Err(index_error(format!(
+                "Hex responded with status {}",
+                response.status()
+            )));
+        }
+
+        Ok(response)
+    }
+}
+
+impl PackageIndex for HexIndex {
+    fn versions(&self, package: &str) -> Result<Vec<Version>, Error> {
+        let package_info: Package = self.get(package, &format!("packages/{}", package))?;
+        // Versions that do not follow semver cannot be depended upon so they
+        // are skipped rather than treated as an error.
+        Ok(package_info
+            .releases
+            .into_iter()
+            .filter_map(|release| Version::parse(&release.version).ok())
+            .collect())
+    }
+
+    fn dependencies(
+        &self,
+        package: &str,
+        version: &Version,
+    ) -> Result<BTreeMap<String, Requirement>, Error> {
+        let release: Release = self.get(
+            package,
+            &format!("packages/{}/releases/{}", package, version),
+        )?;
+        release
+            .requirements
+            .into_iter()
+            .filter(|(_, requirement)| !requirement.optional)
+            .map(|(name, requirement)| {
+                Requirement::parse(&requirement.requirement)
+                    .map(|requirement| (name, requirement))
+                    .map_err(|err| Error::PackageIndex {
+                        package: package.to_string(),
+                        err,
+                    })
+            })
+            .collect()
+    }
+
+    fn download(&self, package: &str, version: &Version) -> Result<Vec<u8>, Error> {
+        use std::io::Read;
+        let url = format!("{}/tarballs/{}-{}.tar", HEX_REPO, package, version);
+        let mut tarball = vec![];
+        self.call(package, &url)?
+            .into_reader()
+            .read_to_end(&mut tarball)
+            .map_err(|e| Error::PackageIndex {
+                package: package.to_string(),
+                err: e.to_string(),
+            })?;
+        Ok(tarball)
+    }
+}
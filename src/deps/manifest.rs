@@ -0,0 +1,144 @@
+use super::Requirements;
+use crate::error::{Error, FileIOAction, FileKind, GleamExpect};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+const HEADER: &str = "# This file was generated by Gleam
+# You typically do not need to edit this file
+
+";
+
+/// The contents of the `gleam.lock` file, which records the exact version
+/// and checksum of every package used by the project so that each build uses
+/// the same code.
+///
+#[derive(Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct Manifest {
+    #[serde(default)]
+    pub packages: Vec<ManifestPackage>,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ManifestPackage {
+    pub name: String,
+    pub version: String,
+    /// The SHA-256 checksum of the package's Hex tarball, in upper case hex.
+    pub checksum: String,
+    /// The names of the packages that this package depends upon.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub requirements: Vec<String>,
+}
+
+impl Manifest {
+    pub fn read(root: &Path) -> Result<Option<Self>, Error> {
+        let path = root.join("gleam.lock");
+        if !path.is_file() {
+            return Ok(None);
+        }
+
+        let src = std::fs::read_to_string(&path).map_err(|e| Error::FileIO {
+            action: FileIOAction::Read,
+            kind: FileKind::File,
+            path: path.clone(),
+            err: Some(e.to_string()),
+        })?;
+        let manifest = Self::parse(&src).map_err(|err| Error::FileIO {
+            action: FileIOAction::Parse,
+            kind: FileKind::File,
+            path,
+            err: Some(err),
+        })?;
+        Ok(Some(manifest))
+    }
+
+    pub fn parse(src: &str) -> Result<Self, String> {
+        let manifest: Self = toml::from_str(src).map_err(|e| e.to_string())?;
+        for package in manifest.packages.iter() {
+            if Version::parse(&package.version).is_err() {
+                return Err(format!("`{}` is not a valid version.", package.version));
+            }
+        }
+        Ok(manifest)
+    }
+
+    pub fn to_toml(&self) -> String {
+        let body = toml::to_string(self).gleam_expect("manifest toml serialisation");
+        format!("{}{}", HEADER, body)
+    }
+
+    pub fn write(&self, root: &Path) -> Result<(), Error> {
+        let path = root.join("gleam.lock");
+        std::fs::write(&path, self.to_toml()).map_err(|e| Error::FileIO {
+            action: FileIOAction::WriteTo,
+            kind: FileKind::File,
+            path,
+            err: Some(e.to_string()),
+        })
+    }
+
+    /// The locked version of each package.
+    pub fn versions(&self) -> BTreeMap<String, Version> {
+        self.packages
+            .iter()
+            .filter_map(|p| Some((p.name.clone(), Version::parse(&p.version).ok()?)))
+            .collect()
+    }
+
+    /// The locked version of each package, with the names of the packages it
+    /// depends upon.
+    pub fn locked(&self) -> BTreeMap<String, (Version, Vec<String>)> {
+        self.packages
+            .iter()
+            .filter_map(|p| {
+                let version = Version::parse(&p.version).ok()?;
+                Some((p.name.clone(), (version, p.requirements.clone())))
+            })
+            .collect()
+    }
+
+    pub fn checksum(&self, name: &str, version: &Version) -> Option<&str> {
+        self.packages
+            .iter()
+            .find(|p| p.name == name && Version::parse(&p.version).ok().as_ref() == Some(version))
+            .map(|p| p.checksum.as_str())
+    }
+
+    /// Whether the locked versions can still be used, i.e. every Hex
+    /// dependency is locked at a version that satisfies its requirement, and
+    /// every locked package is still depended upon by the local packages or
+    /// another locked package.
+    pub fn satisfies(&self, requirements: &Requirements) -> bool {
+        let versions = self.versions();
+        let satisfied = requirements.values().flatten().all(|(name, requirement)| {
+            versions
+                .get(name)
+                .map(|version| requirement.matches(version))
+                .unwrap_or(false)
+        });
+        if !satisfied {
+            return false;
+        }
+
+        let mut reachable = HashSet::new();
+        let mut unvisited: Vec<&str> = requirements
+            .values()
+            .flat_map(|dependencies| dependencies.keys())
+            .map(String::as_str)
+            .collect();
+        while let Some(name) = unvisited.pop() {
+            if !reachable.insert(name) {
+                continue;
+            }
+            match self.packages.iter().find(|p| p.name == name) {
+                Some(package) => unvisited.extend(package.requirements.iter().map(String::as_str)),
+                // A dependency of a locked package is not itself locked
+                None => return false,
+            }
+        }
+        self.packages
+            .iter()
+            .all(|p| reachable.contains(p.name.as_str()))
+    }
+}
@@ -0,0 +1,61 @@
+use crate::config::{read_project_config, ProjectConfig};
+use crate::error::Error;
+use std::path::{Path, PathBuf};
+
+/// A local project that is depended upon using its path.
+///
+#[derive(Debug, PartialEq)]
+pub struct PathPackage {
+    pub name: String,
+    pub dir: PathBuf,
+    pub config: ProjectConfig,
+}
+
+/// Reads the config of each package the project depends upon by path,
+/// including those depended upon by other path packages. Cycles between
+/// packages are an error, as they would be with modules, as is depending upon
+/// a package by a name other than its own.
+///
+pub fn path_packages(root: &Path, config: &ProjectConfig) -> Result<Vec<PathPackage>, Error> {
+    let mut packages = vec![];
+    let mut stack = vec![config.name.clone()];
+    collect(root, config, &mut stack, &mut packages)?;
+    Ok(packages)
+}
+
+fn collect(
+    dir: &Path,
+    config: &ProjectConfig,
+    stack: &mut Vec<String>,
+    packages: &mut Vec<PathPackage>,
+) -> Result<(), Error> {
+    for (name, path) in config.path_dependencies() {
+        if let Some(position) = stack.iter().position(|n| n == name) {
+            return Err(Error::DependencyCycle {
+                packages: stack[position..].to_vec(),
+            });
+        }
+        if packages.iter().any(|p| &p.name == name) {
+            continue;
+        }
+
+        let dir = dir.join(path);
+        let config = read_project_config(&dir.to_string_lossy())?;
+        if &config.name != name {
+            return Err(Error::PathDependencyNameMismatch {
+                path: dir,
+                expected: name.clone(),
+                found: config.name,
+            });
+        }
+        stack.push(name.clone());
+        collect(&dir, &config, stack, packages)?;
+        stack.pop();
+        packages.push(PathPackage {
+            name: name.clone(),
+            dir,
+            config,
+        });
+    }
+    Ok(())
+}
@@ -0,0 +1,403 @@
+use super::*;
+
+fn version(v: &str) -> Version {
+    Version::parse(v).unwrap()
+}
+
+/// The requirements of a root package with the given dependencies.
+///
+fn requirements(dependencies: &[(&str, &str)]) -> Requirements {
+    let dependencies = dependencies
+        .iter()
+        .map(|(name, requirement)| (name.to_string(), Requirement::parse(requirement).unwrap()))
+        .collect();
+    let mut requirements = BTreeMap::new();
+    requirements.insert("root".to_string(), dependencies);
+    requirements
+}
+
+#[test]
+fn requirement_test() {
+    macro_rules! assert_matches {
+        ($requirement:expr, $version:expr, $expected:expr $(,)?) => {
+            let requirement = Requirement::parse($requirement).unwrap();
+            assert_eq!(
+                ($requirement, $version, $expected),
+                (
+                    $requirement,
+                    $version,
+                    requirement.matches(&version($version))
+                )
+            );
+        };
+    }
+
+    assert_matches!("~> 1.2", "1.2.0", true);
+    assert_matches!("~> 1.2", "1.9.3", true);
+    assert_matches!("~> 1.2", "1.1.0", false);
+    assert_matches!("~> 1.2", "2.0.0", false);
+    assert_matches!("~> 1.2.3", "1.2.9", true);
+    assert_matches!("~> 1.2.3", "1.3.0", false);
+    assert_matches!("1.0.0", "1.0.0", true);
+    assert_matches!("1.0.0", "1.0.1", false);
+    assert_matches!("== 1.0.0", "1.0.0", true);
+    assert_matches!("!= 1.0.0", "1.0.0", false);
+    assert_matches!(">= 1.0.0 and < 2.0.0", "1.5.0", true);
+    assert_matches!(">= 1.0.0 and < 2.0.0", "2.0.0", false);
+    assert_matches!("== 1.0.0 or > 3.0.0", "1.0.0", true);
+    assert_matches!("== 1.0.0 or > 3.0.0", "3.0.1", true);
+    assert_matches!("== 1.0.0 or > 3.0.0", "2.0.0", false);
+    assert_matches!("<= 1.0.0", "0.1.0", true);
+
+    // Pre-releases are only matched when the requirement asks for one
+    assert_matches!("~> 1.0", "1.1.0-rc1", false);
+    assert_matches!("~> 1.1.0-rc1", "1.1.0-rc1", true);
+
+    assert!(Requirement::parse("~> 1").is_err());
+    assert!(Requirement::parse("> wibble").is_err());
+    assert!(Requirement::parse("").is_err());
+}
+
+#[test]
+fn resolve_test() {
+    let index = LocalIndex::new(
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("test")
+            .join("package_index"),
+    );
+
+    let resolve = |dependencies: &[(&str, &str)]| {
+        resolve(&index, &requirements(dependencies)).map(|packages| {
+            packages
+                .into_iter()
+                .map(|(name, (version, _))| (name, version))
+                .collect::<BTreeMap<_, _>>()
+        })
+    };
+
+    let resolved = |packages: &[(&str, &str)]| {
+        Ok(packages
+            .iter()
+            .map(|(name, v)| (name.to_string(), version(v)))
+            .collect())
+    };
+
+    assert_eq!(resolved(&[]), resolve(&[]));
+
+    // The newest matching versions are selected
+    assert_eq!(
+        resolved(&[("gleam_stdlib", "0.9.0"), ("wibble", "1.1.0")]),
+        resolve(&[("wibble", "~> 1.0")])
+    );
+
+    // Pre-releases are only selected when asked for
+    assert_eq!(
+        resolved(&[("gleam_stdlib", "1.0.0-rc1")]),
+        resolve(&[("gleam_stdlib", "~> 1.0.0-rc1")])
+    );
+
+    // Older versions are selected when the newest conflict
+    assert_eq!(
+        resolved(&[
+            ("gleam_stdlib", "0.7.0"),
+            ("wibble", "1.0.0"),
+            ("wobble", "1.0.0")
+        ]),
+        resolve(&[("wibble", ">= 1.0.0"), ("wobble", "~> 1.0")])
+    );
+
+    assert_eq!(
+        Err(Error::DependencyConflict {
+            package: "wibble".to_string(),
+            required_by: vec![("root".to_string(), "~> 3.0".to_string())],
+        }),
+        resolve(&[("wibble", "~> 3.0")])
+    );
+
+    assert_eq!(
+        Err(Error::DependencyConflict {
+            package: "gleam_stdlib".to_string(),
+            required_by: vec![
+                ("wibble 2.0.0".to_string(), "~> 0.9".to_string()),
+                ("wobble 1.0.0".to_string(), "== 0.7.0".to_string()),
+            ],
+        }),
+        resolve(&[("wibble", "~> 2.0"), ("wobble", "~> 1.0")])
+    );
+
+    assert_eq!(
+        Err(Error::UnknownPackage {
+            name: "wubble".to_string(),
+        }),
+        resolve(&[("wubble", "~> 1.0")])
+    );
+}
+
+#[test]
+fn manifest_test() {
+    let manifest = Manifest {
+        packages: vec![
+            manifest::ManifestPackage {
+                name: "gleam_stdlib".to_string(),
+                version: "0.8.0".to_string(),
+                checksum: "ABC123".to_string(),
+                requirements: vec![],
+            },
+            manifest::ManifestPackage {
+                name: "wibble".to_string(),
+                version: "1.1.0".to_string(),
+                checksum: "DEF456".to_string(),
+                requirements: vec!["gleam_stdlib".to_string()],
+            },
+        ],
+    };
+
+    let toml = manifest.to_toml();
+    assert_eq!(
+        toml,
+        r#"# This file was generated by Gleam
+# You typically do not need to edit this file
+
+[[packages]]
+name = "gleam_stdlib"
+version = "0.8.0"
+checksum = "ABC123"
+
+[[packages]]
+name = "wibble"
+version = "1.1.0"
+checksum = "DEF456"
+requirements = ["gleam_stdlib"]
+"#
+    );
+    assert_eq!(Ok(manifest), Manifest::parse(&toml));
+
+    assert!(
+        Manifest::parse("[[packages]]\nname = \"a\"\nversion = \"one\"\nchecksum = \"\"").is_err()
+    );
+}
+
+#[test]
+fn manifest_satisfies_test() {
+    let manifest = Manifest::parse(
+        "[[packages]]
+name = \"gleam_stdlib\"
+version = \"0.8.0\"
+checksum = \"ABC\"
+
+[[packages]]
+name = \"wibble\"
+version = \"1.1.0\"
+checksum = \"DEF\"
+requirements = [\"gleam_stdlib\"]",
+    )
+    .unwrap();
+
+    assert!(manifest.satisfies(&requirements(&[("wibble", "~> 1.0")])));
+    assert!(manifest.satisfies(&requirements(&[
+        ("wibble", "~> 1.0"),
+        ("gleam_stdlib", "~> 0.8")
+    ])));
+    assert!(!manifest.satisfies(&requirements(&[("wibble", "~> 2.0")])));
+    assert!(!manifest.satisfies(&requirements(&[("wobble", "~> 1.0")])));
+
+    // Packages that are no longer depended upon make the lock stale
+    assert!(!manifest.satisfies(&requirements(&[])));
+    assert!(!manifest.satisfies(&requirements(&[("gleam_stdlib", "~> 0.8")])));
+
+    // As do dependencies of locked packages that are not locked
+    let manifest = Manifest::parse(
+        "[[packages]]
+name = \"wibble\"
+version = \"1.1.0\"
+checksum = \"DEF\"
+requirements = [\"gleam_stdlib\"]",
+    )
+    .unwrap();
+    assert!(!manifest.satisfies(&requirements(&[("wibble", "~> 1.0")])));
+}
+
+/// Builds a tarball in the format Hex uses, with the source files inside a
+/// gzipped `contents.tar.gz`.
+///
+fn hex_tarball(files: &[(&str, &str)]) -> Vec<u8> {
+    fn append(builder: &mut tar::Builder<impl std::io::Write>, path: &str, data: &[u8]) {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, path, data).unwrap();
+    }
+
+    let encoder = flate2::write::GzEncoder::new(vec![], flate2::Compression::default());
+    let mut contents = tar::Builder::new(encoder);
+    for (path, text) in files {
+        append(&mut contents, path, text.as_bytes());
+    }
+    let contents = contents.into_inner().unwrap().finish().unwrap();
+
+    let mut outer = tar::Builder::new(vec![]);
+    append(&mut outer, "VERSION", b"3");
+    append(&mut outer, "contents.tar.gz", &contents);
+    outer.into_inner().unwrap()
+}
+
+#[test]
+fn download_test() {
+    let dir = std::env::temp_dir().join("gleam_deps_download_test");
+    let _ = std::fs::remove_dir_all(&dir);
+    let index_dir = dir.join("index");
+    let root = dir.join("project");
+    std::fs::create_dir_all(&index_dir).unwrap();
+    std::fs::create_dir_all(&root).unwrap();
+
+    std::fs::write(
+        index_dir.join("wibble.toml"),
+        "[[releases]]\nversion = \"1.0.0\"\n\n[[releases]]\nversion = \"1.1.0\"\n",
+    )
+    .unwrap();
+    let tarball = hex_tarball(&[("src/wibble.gleam", "pub fn one() { 1 }")]);
+    std::fs::write(index_dir.join("wibble-1.0.0.tar"), &tarball).unwrap();
+    std::fs::write(index_dir.join("wibble-1.1.0.tar"), &tarball).unwrap();
+
+    let index = LocalIndex::new(index_dir.clone());
+    let requirements = requirements(&[("wibble", "~> 1.0")]);
+    let checksum = fetch::checksum(&tarball);
+    let locked = |version: &str| Manifest {
+        packages: vec![manifest::ManifestPackage {
+            name: "wibble".to_string(),
+            version: version.to_string(),
+            checksum: checksum.clone(),
+            requirements: vec![],
+        }],
+    };
+
+    // Resolved versions are unpacked and locked
+    assert_eq!(
+        Ok(locked("1.1.0")),
+        download(&index, &root, &requirements, false)
+    );
+    assert_eq!(Ok(Some(locked("1.1.0"))), Manifest::read(&root));
+    assert_eq!(
+        "pub fn one() { 1 }",
+        std::fs::read_to_string(package_dir(&root, "wibble").join("src/wibble.gleam")).unwrap()
+    );
+
+    // Locked versions are used in preference to resolving again
+    locked("1.0.0").write(&root).unwrap();
+    assert_eq!(
+        Ok(locked("1.0.0")),
+        download(&index, &root, &requirements, false)
+    );
+
+    // Unless the dependencies are being updated
+    assert_eq!(
+        Ok(locked("1.1.0")),
+        download(&index, &root, &requirements, true)
+    );
+
+    // Packages that are no longer required are removed from the lock
+    let mut removed = locked("1.1.0");
+    removed.packages.push(manifest::ManifestPackage {
+        name: "wobble".to_string(),
+        version: "1.0.0".to_string(),
+        checksum: "0000".to_string(),
+        requirements: vec![],
+    });
+    removed.write(&root).unwrap();
+    assert_eq!(
+        Ok(locked("1.1.0")),
+        download(&index, &root, &requirements, false)
+    );
+    assert_eq!(Ok(Some(locked("1.1.0"))), Manifest::read(&root));
+
+    // A package that does not match its locked checksum is rejected
+    let mut tampered = locked("1.1.0");
+    tampered.packages[0].checksum = "0000".to_string();
+    tampered.write(&root).unwrap();
+    assert_eq!(
+        Err(Error::ChecksumMismatch {
+            package: "wibble".to_string(),
+            version: "1.1.0".to_string(),
+            expected: "0000".to_string(),
+            actual: checksum.clone(),
+        }),
+        download(&index, &root, &requirements, false)
+    );
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn path_packages_test() {
+    let dir = std::env::temp_dir().join("gleam_deps_path_packages_test");
+    let _ = std::fs::remove_dir_all(&dir);
+    let write_config = |name: &str, toml: &str| {
+        std::fs::create_dir_all(dir.join(name)).unwrap();
+        std::fs::write(dir.join(name).join("gleam.toml"), toml).unwrap();
+    };
+
+    write_config(
+        "app",
+        "name = \"app\"\n[dependencies]\none = { path = \"../one\" }\ntwo = { path = \"../two\" }\n",
+    );
+    write_config(
+        "one",
+        "name = \"one\"\n[dependencies]\ntwo = { path = \"../two\" }\nwibble = \"~> 1.0\"\n",
+    );
+    write_config("two", "name = \"two\"\n");
+
+    let root = dir.join("app");
+    let config = crate::config::read_project_config(&root.to_string_lossy()).unwrap();
+    let packages = path_packages(&root, &config).unwrap();
+    assert_eq!(
+        vec![
+            ("two", root.join("../one/../two")),
+            ("one", root.join("../one"))
+        ],
+        packages
+            .iter()
+            .map(|p| (p.name.as_str(), p.dir.clone()))
+            .collect::<Vec<_>>()
+    );
+
+    // The Hex dependencies of path packages are resolved too
+    let requirements = super::requirements(&config, &packages);
+    assert_eq!(
+        vec!["app", "one", "two"],
+        requirements.keys().collect::<Vec<_>>()
+    );
+    assert_eq!(1, requirements["one"].len());
+
+    // Path packages must be depended upon by their own name
+    write_config(
+        "one",
+        "name = \"uno\"\n[dependencies]\ntwo = { path = \"../two\" }\n",
+    );
+    assert_eq!(
+        Err(Error::PathDependencyNameMismatch {
+            path: root.join("../one"),
+            expected: "one".to_string(),
+            found: "uno".to_string(),
+        }),
+        path_packages(&root, &config)
+    );
+    write_config(
+        "one",
+        "name = \"one\"\n[dependencies]\ntwo = { path = \"../two\" }\n",
+    );
+
+    // Path packages cannot depend upon each other in a cycle
+    write_config(
+        "two",
+        "name = \"two\"\n[dependencies]\napp = { path = \"../app\" }\n",
+    );
+    assert_eq!(
+        Err(Error::DependencyCycle {
+            packages: vec!["app".to_string(), "one".to_string(), "two".to_string()],
+        }),
+        path_packages(&root, &config)
+    );
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -1,19 +1,84 @@
+pub mod json;
+#[cfg(test)]
+mod tests;
+
 use crate::{
     ast::{Statement, TypedStatement},
-    error::GleamExpect,
-    format, pretty,
-    project::{Analysed, ModuleOrigin, OutputFile, ProjectConfig},
+    config::{ProjectConfig, Repository},
+    error::{Error, FileIOAction, FileKind, GleamExpect},
+    format,
+    pretty::{self, Documentable},
+    project::{Analysed, CopiedFile, ModuleOrigin, OutputFile},
+    typ,
 };
 use askama::Template;
 use itertools::Itertools;
-use std::path::PathBuf;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use strum_macros::{Display, EnumString, EnumVariantNames};
 
 const MAX_COLUMNS: isize = 65;
 
+/// A format that documentation can be generated in, selected with `gleam
+/// docs --format`.
+///
+#[derive(Debug, PartialEq, Clone, Copy, Display, EnumString, EnumVariantNames)]
+#[strum(serialize_all = "kebab_case")]
+pub enum Format {
+    /// A website, written to the `doc` directory.
+    Html,
+    /// A description of the public API, written to `doc.json`.
+    Json,
+}
+
+/// An extra page of documentation, with the markdown read from the file
+/// given in the project config.
+///
+#[derive(Debug, PartialEq)]
+pub struct Page {
+    pub title: String,
+    /// The markdown file of the page, relative to the project root.
+    pub path: String,
+    pub text: String,
+}
+
+/// Reads the extra pages of documentation listed in the project config.
+///
+pub fn read_pages(root: &Path, project_config: &ProjectConfig) -> Result<Vec<Page>, Error> {
+    project_config
+        .docs
+        .pages
+        .iter()
+        .map(|page| {
+            let path = root.join(&page.path);
+            let text = std::fs::read_to_string(&path).map_err(|e| Error::FileIO {
+                action: FileIOAction::Read,
+                kind: FileKind::File,
+                path,
+                err: Some(e.to_string()),
+            })?;
+            Ok(Page {
+                title: page.title.clone(),
+                path: page.path.clone(),
+                text,
+            })
+        })
+        .collect()
+}
+
+/// Generates the documentation of the project into `dir`. The README, found
+/// in the project `root`, is used as the index page, and is followed by any
+/// extra pages.
+///
 pub fn generate_html(
     project_config: &ProjectConfig,
     analysed: &[Analysed],
+    readme: &str,
+    extra_pages: &[Page],
+    root: &Path,
     files: &mut Vec<OutputFile>,
+    copied: &mut Vec<CopiedFile>,
     dir: &PathBuf,
 ) {
     let modules = analysed.iter().filter(|m| m.origin == ModuleOrigin::Src);
@@ -28,48 +93,144 @@ pub fn generate_html(
         .collect();
     modules_links.sort();
 
-    let pages = &[Link {
+    let pages: Vec<_> = std::iter::once(Link {
         name: "README".to_string(),
         path: "".to_string(),
-    }];
-    let links = &[];
+    })
+    .chain(extra_pages.iter().map(|page| Link {
+        name: page.title.clone(),
+        path: page_path(&page.path).to_string(),
+    }))
+    .collect();
+    let pages = pages.as_slice();
+    let links: Vec<_> = project_config
+        .repository
+        .url()
+        .map(|path| Link {
+            name: "Repository".to_string(),
+            path,
+        })
+        .into_iter()
+        .chain(project_config.links.iter().map(|l| Link {
+            name: l.title.clone(),
+            path: l.href.clone(),
+        }))
+        .collect();
+    let links = links.as_slice();
+
+    // Relative links in the README and extra pages are rewritten to work in
+    // the generated documentation
+    let page_links = PageLinks {
+        pages: std::iter::once(("README.md".to_string(), "".to_string()))
+            .chain(extra_pages.iter().filter_map(|page| {
+                project_path("", &page.path).map(|path| (path, page_path(&page.path).to_string()))
+            }))
+            .collect(),
+        repository: &project_config.repository,
+        version: &project_config.version,
+    };
+    let mut assets = vec![];
 
     // Generate README page
+    let content = render_readme(readme, "README.md", ".", &page_links, &mut assets);
     let readme = PageTemplate {
         unnest: ".".to_string(),
         links,
         pages,
         modules: &modules_links,
-        content: "", // TODO
+        content: &content,
         project_name: &project_config.name,
         page_title: &project_config.name,
-        project_version: "", // TODO
+        project_version: &project_config.version,
     };
     files.push(OutputFile {
         path: dir.join("index.html"),
         text: readme.render().gleam_expect("README template rendering"),
     });
 
+    // Generate extra pages
+    for page in extra_pages {
+        let segments: Vec<String> = page_path(&page.path)
+            .split('/')
+            .map(|s| s.to_string())
+            .collect();
+        let mut page_dir = dir.clone();
+        page_dir.extend(segments.iter());
+        let unnest = unnest(&segments);
+        let content = render_readme(&page.text, &page.path, &unnest, &page_links, &mut assets);
+        let template = PageTemplate {
+            unnest,
+            links,
+            pages,
+            modules: &modules_links,
+            content: &content,
+            project_name: &project_config.name,
+            page_title: &format!("{} - {}", page.title, project_config.name),
+            project_version: &project_config.version,
+        };
+        files.push(OutputFile {
+            path: page_dir.join("index.html"),
+            text: template.render().gleam_expect("Page template rendering"),
+        });
+    }
+
+    // Copy the images shown in the README and extra pages
+    assets.sort();
+    assets.dedup();
+    copied.extend(assets.into_iter().map(|path| CopiedFile {
+        from: root.join(&path),
+        to: dir.join(&path),
+    }));
+
     // Generate module documentation pages
+    let type_links = TypeLinks::new(analysed, root);
+    let source_url_template = project_config.source_url();
     for module in modules {
         let name = module.name.join("/");
+        let source_url = |statement: &TypedStatement| {
+            source_url_template.as_ref().map(|template| {
+                source_url(template, &project_config.version, root, module, statement)
+            })
+        };
+        let documentation = render_markdown(&module.ast.documentation.join("\n"));
         let template = ModuleTemplate {
-            unnest: module.name.iter().map(|_| "..").intersperse("/").collect(),
+            unnest: unnest(&module.name),
             links,
             pages,
-            documentation: "",
+            documentation: &documentation,
             modules: modules_links.as_slice(),
             project_name: &project_config.name,
             page_title: &format!("{} - {}", name, project_config.name),
             module_name: name,
-            project_version: "", // TODO
+            project_version: &project_config.version,
             functions: {
-                let mut f: Vec<_> = module.ast.statements.iter().flat_map(function).collect();
+                let links = |m: &[String], n: &str| type_links.url(&module.name, m, n);
+                let mut f: Vec<_> = module
+                    .ast
+                    .statements
+                    .iter()
+                    .flat_map(|s| {
+                        function(s, &module.ast.type_info, &links).map(|f| Function {
+                            source_url: source_url(s),
+                            ..f
+                        })
+                    })
+                    .collect();
                 f.sort();
                 f
             },
             types: {
-                let mut t: Vec<_> = module.ast.statements.iter().flat_map(type_).collect();
+                let mut t: Vec<_> = module
+                    .ast
+                    .statements
+                    .iter()
+                    .flat_map(|s| {
+                        type_(s).map(|t| Type {
+                            source_url: source_url(s),
+                            ..t
+                        })
+                    })
+                    .collect();
                 t.sort();
                 t
             },
@@ -87,46 +248,308 @@ pub fn generate_html(
         });
     }
 
+    // Generate the search index. It is a script rather than a JSON file so
+    // that it can be loaded when the docs are viewed from the file system.
+    let search_items: Vec<_> = analysed
+        .iter()
+        .filter(|m| m.origin == ModuleOrigin::Src)
+        .flat_map(search_items)
+        .collect();
+    files.push(OutputFile {
+        path: dir.join("search_index.js"),
+        text: format!(
+            "window.searchIndex = {};\n",
+            serde_json::to_string(&search_items).gleam_expect("Search index serialization")
+        ),
+    });
+
     // Render static assets
     files.push(OutputFile {
         path: dir.join("index.css"),
         text: std::include_str!("../templates/index.css").to_string(),
     });
+    files.push(OutputFile {
+        path: dir.join("search.js"),
+        text: std::include_str!("../templates/search.js").to_string(),
+    });
 }
 
-fn function<'a>(statement: &'a TypedStatement) -> Option<Function<'a>> {
-    let mut formatter = format::Formatter::new();
+/// The entries of the search index for a module: the module itself, and
+/// its public types, constructors, and functions.
+///
+fn search_items(module: &Analysed) -> Vec<SearchItem> {
+    let module_name = module.name.join("/");
+    let item = |kind, name: &str, signature, doc: &Option<String>, anchor: &str| SearchItem {
+        kind,
+        module: module_name.clone(),
+        name: name.to_string(),
+        signature,
+        doc: first_line(doc),
+        url: format!("{}/#{}", module_name, anchor),
+    };
+
+    let mut items = vec![SearchItem {
+        kind: "module",
+        module: module_name.clone(),
+        name: module_name.clone(),
+        signature: "".to_string(),
+        doc: first_line(&Some(module.ast.documentation.join("\n"))),
+        url: format!("{}/", module_name),
+    }];
+
+    for statement in module.ast.statements.iter() {
+        if let Some(f) = function(statement, &module.ast.type_info, &|_, _| None) {
+            items.push(item(
+                "function",
+                f.name,
+                f.signature,
+                statement_doc(statement),
+                f.name,
+            ));
+        }
+        if let Some(t) = type_(statement) {
+            items.push(item(
+                "type",
+                t.name,
+                t.definition,
+                statement_doc(statement),
+                t.name,
+            ));
+            if let Statement::CustomType { constructors, .. } = statement {
+                for (constructor, definition) in constructors.iter().zip(t.constructors) {
+                    items.push(item(
+                        "constructor",
+                        &constructor.name,
+                        definition.definition,
+                        &constructor.doc,
+                        t.name,
+                    ));
+                }
+            }
+        }
+    }
+    items
+}
+
+fn statement_doc(statement: &TypedStatement) -> &Option<String> {
     match statement {
+        Statement::Fn { doc, .. }
+        | Statement::TypeAlias { doc, .. }
+        | Statement::CustomType { doc, .. }
+        | Statement::ExternalFn { doc, .. }
+        | Statement::ExternalType { doc, .. } => doc,
+        Statement::Import { .. } => &None,
+    }
+}
+
+/// The first non-blank line of some documentation, used as a summary.
+///
+fn first_line(doc: &Option<String>) -> String {
+    doc.iter()
+        .flat_map(|doc| doc.lines())
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or("")
+        .to_string()
+}
+
+fn function<'a>(
+    statement: &'a TypedStatement,
+    type_info: &typ::Module,
+    links: &dyn Fn(&[String], &str) -> Option<String>,
+) -> Option<Function<'a>> {
+    let (external, name, doc, arg_names): (_, _, _, Vec<_>) = match statement {
         Statement::ExternalFn {
             public: true,
             name,
             doc,
-            retrn,
             args,
             ..
-        } => Some(Function {
+        } => (
+            true,
             name,
-            signature: print(formatter.external_fn_signature(true, name, args, retrn)),
-            documentation: markdown_documentation(doc),
-        }),
+            doc,
+            args.iter()
+                .map(|arg| arg.label.clone().map(|label| label.to_doc()))
+                .collect(),
+        ),
 
         Statement::Fn {
             public: true,
             name,
             doc,
             args,
-            return_type: ret,
             ..
-        } => Some(Function {
+        } => (
+            false,
             name,
-            documentation: markdown_documentation(doc),
-            signature: print(formatter.docs_fn_signature(true, name, args, ret.clone())),
-        }),
+            doc,
+            args.iter().map(|arg| Some((&arg.names).to_doc())).collect(),
+        ),
 
-        _ => None,
+        _ => return None,
+    };
+
+    // The inferred type of the function is used so that the types of any
+    // unannotated arguments are shown too
+    let (arg_types, return_type) = match type_info.values.get(name).map(|v| v.typ.as_ref()) {
+        Some(typ::Type::Fn { args, retrn }) => (args, retrn),
+        _ => crate::error::fatal_compiler_bug("Public function not found in module type info"),
+    };
+    let args = arg_names
+        .into_iter()
+        .zip(arg_types.iter().cloned())
+        .collect();
+    let mut printer = typ::pretty::Printer::with_links(links);
+
+    Some(Function {
+        name,
+        signature: print(format::Formatter::new().docs_fn_signature(
+            true,
+            external,
+            name,
+            args,
+            return_type,
+            &mut printer,
+        )),
+        documentation: markdown_documentation(doc),
+        source_url: None,
+    })
+}
+
+/// The URL of the source of a statement, made by filling in the placeholders
+/// of the `source_url` template from the project config.
+///
+fn source_url(
+    template: &str,
+    version: &str,
+    root: &Path,
+    module: &Analysed,
+    statement: &TypedStatement,
+) -> String {
+    let path = module
+        .path
+        .strip_prefix(root)
+        .unwrap_or(&module.path)
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .join("/");
+    let (line_start, line_end) = source_lines(&module.src, statement);
+    template
+        .replace("{version}", version)
+        .replace("{path}", &path)
+        .replace("{line_start}", &line_start.to_string())
+        .replace("{line_end}", &line_end.to_string())
+}
+
+/// The first and last lines of the source of a statement, counting from 1.
+///
+fn source_lines(src: &str, statement: &TypedStatement) -> (usize, usize) {
+    let location = statement.location();
+    let end = match statement {
+        // The location of a custom type only covers its head, so the end is
+        // found from its constructors and the closing brace that follows them
+        Statement::CustomType { constructors, .. } => {
+            let last = constructors
+                .iter()
+                .map(|c| c.location.end)
+                .max()
+                .unwrap_or(location.end);
+            src[last..].find('}').map(|i| last + i).unwrap_or(last)
+        }
+        _ => location.end,
+    };
+    let line = |offset: usize| src[..offset.min(src.len())].matches('\n').count() + 1;
+    (line(location.start), line(end))
+}
+
+/// Where the documentation for the types defined in each module can be
+/// found, so that types in function signatures can be linked to it.
+///
+struct TypeLinks<'a> {
+    package_modules: HashSet<&'a [String]>,
+    dependency_packages: HashMap<&'a [String], &'a str>,
+}
+
+impl<'a> TypeLinks<'a> {
+    /// Dependencies are linked to HexDocs when they were downloaded from Hex
+    /// into the `_build` directory of the project `root`, where each is
+    /// unpacked into a directory named after the package. Path dependencies
+    /// and rebar3 checkouts are not published, so are not linked.
+    ///
+    fn new(analysed: &'a [Analysed], root: &Path) -> Self {
+        let mut package_modules = HashSet::new();
+        let mut dependency_packages = HashMap::new();
+        for module in analysed {
+            match module.origin {
+                ModuleOrigin::Src => {
+                    package_modules.insert(module.name.as_slice());
+                }
+                ModuleOrigin::Dependency => {
+                    let package = module.source_base_path.parent().and_then(|dir| {
+                        let name = dir.file_name()?.to_str()?;
+                        if dir == crate::deps::package_dir(root, name) {
+                            Some(name)
+                        } else {
+                            None
+                        }
+                    });
+                    if let Some(package) = package {
+                        dependency_packages.insert(module.name.as_slice(), package);
+                    }
+                }
+                ModuleOrigin::Test => (),
+            }
+        }
+        Self {
+            package_modules,
+            dependency_packages,
+        }
+    }
+
+    /// The URL of the documentation for a type, relative to the page of the
+    /// `current` module. Types defined in the package link to its pages,
+    /// and types defined in Hex dependencies link to their docs on HexDocs.
+    /// Built in types have no documentation to link to.
+    ///
+    fn url(&self, current: &[String], module: &[String], name: &str) -> Option<String> {
+        if module.is_empty() {
+            None
+        } else if module == current {
+            Some(format!("#{}", name))
+        } else if self.package_modules.contains(module) {
+            Some(format!(
+                "{}/{}/#{}",
+                unnest(current),
+                module.join("/"),
+                name
+            ))
+        } else {
+            self.dependency_packages.get(module).map(|package| {
+                format!(
+                    "https://hexdocs.pm/{}/{}/#{}",
+                    package,
+                    module.join("/"),
+                    name
+                )
+            })
+        }
     }
 }
 
+/// The path of an extra page within the documentation, which is the path of
+/// its markdown file without the `.md` extension.
+///
+fn page_path(path: &str) -> &str {
+    path.trim_end_matches(".md")
+}
+
+/// The relative path from the page of a module to the documentation root.
+///
+fn unnest(module: &[String]) -> String {
+    module.iter().map(|_| "..").intersperse("/").collect()
+}
+
 fn markdown_documentation(doc: &Option<String>) -> String {
     match doc {
         None => "".to_string(),
@@ -141,6 +564,116 @@ fn render_markdown(text: &str) -> String {
     s
 }
 
+/// How the relative links of the README and extra pages are rewritten, so
+/// that they still work once the documentation is published.
+///
+struct PageLinks<'a> {
+    /// The markdown files rendered as pages, relative to the project root,
+    /// and the paths of their pages within the documentation.
+    pages: HashMap<String, String>,
+    repository: &'a Repository,
+    version: &'a str,
+}
+
+impl<'a> PageLinks<'a> {
+    /// Rewrites a URL of the markdown file `source`, rendered as the page
+    /// `unnest` from the root of the documentation, or returns `None` if it
+    /// is left as it is. Images are copied into the documentation, so their
+    /// paths within the project are added to `assets`. Links to pages point
+    /// at the page, and links to any other file of the project point at the
+    /// file in the repository.
+    ///
+    fn rewrite(
+        &self,
+        url: &str,
+        is_image: bool,
+        source: &str,
+        unnest: &str,
+        assets: &mut Vec<String>,
+    ) -> Option<String> {
+        if !is_relative_url(url) {
+            return None;
+        }
+        let (path, suffix) = url.split_at(url.find(|c| c == '#' || c == '?').unwrap_or(url.len()));
+        let path = project_path(source, path).filter(|path| !path.is_empty())?;
+        if is_image {
+            let url = format!("{}/{}{}", unnest, path, suffix);
+            assets.push(path);
+            return Some(url);
+        }
+        match self.pages.get(&path) {
+            Some(page) if page.is_empty() => Some(format!("{}/{}", unnest, suffix)),
+            Some(page) => Some(format!("{}/{}/{}", unnest, page, suffix)),
+            None => self
+                .repository
+                .file_url(self.version, &path)
+                .map(|url| format!("{}{}", url, suffix)),
+        }
+    }
+}
+
+/// The path within the project of a relative URL found in the markdown file
+/// `source`, or `None` if it is outside of the project.
+///
+fn project_path(source: &str, url: &str) -> Option<String> {
+    let mut segments: Vec<&str> = source.split('/').collect();
+    let _ = segments.pop();
+    for segment in url.split('/') {
+        match segment {
+            "" | "." => (),
+            ".." => {
+                let _ = segments.pop()?;
+            }
+            segment => segments.push(segment),
+        }
+    }
+    Some(segments.join("/"))
+}
+
+/// Renders the README or an extra page from the markdown file `source`,
+/// rewriting its relative links with `links`.
+///
+fn render_readme<'a>(
+    text: &'a str,
+    source: &str,
+    unnest: &str,
+    links: &PageLinks<'_>,
+    assets: &mut Vec<String>,
+) -> String {
+    use pulldown_cmark::{CowStr, Event, Tag};
+    let mut rewrite = |url: CowStr<'a>, is_image| {
+        links
+            .rewrite(&url, is_image, source, unnest, assets)
+            .map_or(url, CowStr::from)
+    };
+
+    // Only the start of a link is rendered with its URL
+    let mut s = String::with_capacity(text.len() * 3 / 2);
+    let p = pulldown_cmark::Parser::new(&*text).map(|event| match event {
+        Event::Start(Tag::Link(kind, url, title)) => {
+            Event::Start(Tag::Link(kind, rewrite(url, false), title))
+        }
+        Event::Start(Tag::Image(kind, url, title)) => {
+            Event::Start(Tag::Image(kind, rewrite(url, true), title))
+        }
+        event => event,
+    });
+    pulldown_cmark::html::push_html(&mut s, p);
+    s
+}
+
+/// Whether the URL is a path relative to the current page, rather than an
+/// absolute URL, an absolute path, or a fragment of the current page.
+///
+fn is_relative_url(url: &str) -> bool {
+    use regex::Regex;
+    lazy_static! {
+        static ref SCHEME: Regex =
+            Regex::new("^[A-Za-z][A-Za-z0-9+.-]*:").gleam_expect("doc::is_relative_url() regex");
+    }
+    !url.is_empty() && !url.starts_with('/') && !url.starts_with('#') && !SCHEME.is_match(url)
+}
+
 fn type_<'a>(statement: &'a TypedStatement) -> Option<Type<'a>> {
     let mut formatter = format::Formatter::new();
     match statement {
@@ -154,6 +687,8 @@ fn type_<'a>(statement: &'a TypedStatement) -> Option<Type<'a>> {
             name,
             definition: print(formatter.external_type(true, name.as_str(), args)),
             documentation: markdown_documentation(doc),
+            constructors: vec![],
+            source_url: None,
         }),
 
         Statement::CustomType {
@@ -167,6 +702,14 @@ fn type_<'a>(statement: &'a TypedStatement) -> Option<Type<'a>> {
             name,
             definition: print(formatter.custom_type(true, name, args, cs.as_slice())),
             documentation: markdown_documentation(doc),
+            constructors: cs
+                .iter()
+                .map(|constructor| TypeConstructor {
+                    definition: print(formatter.record_constructor(constructor)),
+                    documentation: markdown_documentation(&constructor.doc),
+                })
+                .collect(),
+            source_url: None,
         }),
 
         Statement::TypeAlias {
@@ -180,6 +723,8 @@ fn type_<'a>(statement: &'a TypedStatement) -> Option<Type<'a>> {
             name,
             definition: print(formatter.type_alias(true, name, args, typ)),
             documentation: markdown_documentation(doc),
+            constructors: vec![],
+            source_url: None,
         }),
 
         _ => None,
@@ -201,6 +746,7 @@ struct Function<'a> {
     name: &'a str,
     signature: String,
     documentation: String,
+    source_url: Option<String>,
 }
 
 #[derive(PartialEq, Eq, PartialOrd, Ord)]
@@ -208,6 +754,26 @@ struct Type<'a> {
     name: &'a str,
     definition: String,
     documentation: String,
+    constructors: Vec<TypeConstructor>,
+    source_url: Option<String>,
+}
+
+/// An entry of the search index.
+///
+#[derive(Serialize, Debug, PartialEq)]
+struct SearchItem {
+    kind: &'static str,
+    module: String,
+    name: String,
+    signature: String,
+    doc: String,
+    url: String,
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+struct TypeConstructor {
+    definition: String,
+    documentation: String,
 }
 
 #[derive(Template)]
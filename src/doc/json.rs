@@ -0,0 +1,279 @@
+use crate::{
+    ast::{ArgNames, Statement, TypedStatement},
+    config::ProjectConfig,
+    error::GleamExpect,
+    pretty,
+    project::{Analysed, ModuleOrigin},
+    typ::{self, pretty::Printer},
+};
+use serde::Serialize;
+
+/// A description of the public API of a package, for use by tools other
+/// than the HTML documentation. Types are written as they would be in Gleam
+/// code, and documentation is the markdown of the doc comments.
+///
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Package {
+    pub name: String,
+    pub version: String,
+    pub modules: Vec<Module>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Module {
+    pub name: String,
+    pub documentation: Option<String>,
+    pub types: Vec<Type>,
+    pub functions: Vec<Function>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum Type {
+    Custom {
+        name: String,
+        parameters: Vec<String>,
+        documentation: Option<String>,
+        constructors: Vec<Constructor>,
+    },
+    External {
+        name: String,
+        parameters: Vec<String>,
+        documentation: Option<String>,
+    },
+    Alias {
+        name: String,
+        parameters: Vec<String>,
+        documentation: Option<String>,
+        alias: String,
+    },
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Constructor {
+    pub name: String,
+    pub documentation: Option<String>,
+    pub parameters: Vec<Parameter>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Function {
+    pub name: String,
+    pub documentation: Option<String>,
+    pub external: bool,
+    pub parameters: Vec<Parameter>,
+    pub return_type: String,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Parameter {
+    pub label: Option<String>,
+    #[serde(rename = "type")]
+    pub typ: String,
+}
+
+/// Renders the public API of the modules of the project as JSON.
+///
+pub fn generate(project_config: &ProjectConfig, analysed: &[Analysed]) -> String {
+    serde_json::to_string_pretty(&package(project_config, analysed))
+        .gleam_expect("Package documentation serialization")
+}
+
+pub fn package(project_config: &ProjectConfig, analysed: &[Analysed]) -> Package {
+    let mut modules: Vec<_> = analysed
+        .iter()
+        .filter(|m| m.origin == ModuleOrigin::Src)
+        .map(module)
+        .collect();
+    modules.sort_by(|a, b| a.name.cmp(&b.name));
+    Package {
+        name: project_config.name.clone(),
+        version: project_config.version.clone(),
+        modules,
+    }
+}
+
+fn module(module: &Analysed) -> Module {
+    let type_info = &module.type_info;
+    let documentation = module.ast.documentation.join("\n");
+    Module {
+        name: module.name.join("/"),
+        documentation: if documentation.is_empty() {
+            None
+        } else {
+            Some(documentation)
+        },
+        types: module
+            .ast
+            .statements
+            .iter()
+            .flat_map(|s| type_(s, type_info))
+            .collect(),
+        functions: module
+            .ast
+            .statements
+            .iter()
+            .flat_map(|s| function(s, type_info))
+            .collect(),
+    }
+}
+
+fn type_(statement: &TypedStatement, type_info: &typ::Module) -> Option<Type> {
+    // The same printer is used for the whole type so that its parameters
+    // have the same names wherever they appear
+    let mut printer = Printer::new();
+
+    match statement {
+        Statement::CustomType {
+            public: true,
+            name,
+            args,
+            doc,
+            constructors,
+            ..
+        } => {
+            let parameters = parameters(&mut printer, type_info, name, args);
+            let constructors = constructors
+                .iter()
+                .map(|constructor| {
+                    let args = match type_info.values.get(&constructor.name).map(|v| &*v.typ) {
+                        Some(typ::Type::Fn { args, .. }) => args.as_slice(),
+                        _ => &[],
+                    };
+                    Constructor {
+                        name: constructor.name.clone(),
+                        documentation: constructor.doc.clone(),
+                        parameters: constructor
+                            .args
+                            .iter()
+                            .zip(args)
+                            .map(|((label, _), typ)| Parameter {
+                                label: label.clone(),
+                                typ: print(&mut printer, typ),
+                            })
+                            .collect(),
+                    }
+                })
+                .collect();
+            Some(Type::Custom {
+                name: name.clone(),
+                parameters,
+                documentation: doc.clone(),
+                constructors,
+            })
+        }
+
+        Statement::ExternalType {
+            public: true,
+            name,
+            args,
+            doc,
+            ..
+        } => Some(Type::External {
+            name: name.clone(),
+            parameters: parameters(&mut printer, type_info, name, args),
+            documentation: doc.clone(),
+        }),
+
+        Statement::TypeAlias {
+            public: true,
+            alias: name,
+            args,
+            typ,
+            doc,
+            ..
+        } => {
+            let parameters = parameters(&mut printer, type_info, name, args);
+            Some(Type::Alias {
+                name: name.clone(),
+                parameters,
+                documentation: doc.clone(),
+                alias: print(&mut printer, typ),
+            })
+        }
+
+        _ => None,
+    }
+}
+
+fn function(statement: &TypedStatement, type_info: &typ::Module) -> Option<Function> {
+    let (external, name, doc, labels): (_, _, _, Vec<_>) = match statement {
+        Statement::ExternalFn {
+            public: true,
+            name,
+            doc,
+            args,
+            ..
+        } => (
+            true,
+            name,
+            doc,
+            args.iter().map(|arg| arg.label.clone()).collect(),
+        ),
+
+        Statement::Fn {
+            public: true,
+            name,
+            doc,
+            args,
+            ..
+        } => (
+            false,
+            name,
+            doc,
+            args.iter()
+                .map(|arg| match &arg.names {
+                    ArgNames::LabelledDiscard { label, .. }
+                    | ArgNames::NamedLabelled { label, .. } => Some(label.clone()),
+                    ArgNames::Discard { .. } | ArgNames::Named { .. } => None,
+                })
+                .collect(),
+        ),
+
+        _ => return None,
+    };
+
+    let (args, return_type) = match type_info.values.get(name).map(|v| v.typ.as_ref()) {
+        Some(typ::Type::Fn { args, retrn }) => (args, retrn),
+        _ => crate::error::fatal_compiler_bug("Public function not found in module type info"),
+    };
+    let mut printer = Printer::new();
+    Some(Function {
+        name: name.clone(),
+        documentation: doc.clone(),
+        external,
+        parameters: labels
+            .into_iter()
+            .zip(args.iter())
+            .map(|(label, typ)| Parameter {
+                label,
+                typ: print(&mut printer, typ),
+            })
+            .collect(),
+        return_type: print(&mut printer, return_type),
+    })
+}
+
+/// The parameters of a type, as they are printed in the rest of the type.
+///
+fn parameters(
+    printer: &mut Printer<'_>,
+    type_info: &typ::Module,
+    name: &str,
+    args: &[String],
+) -> Vec<String> {
+    match type_info.types.get(name) {
+        Some(constructor) => constructor
+            .parameters
+            .iter()
+            .map(|p| print(printer, p))
+            .collect(),
+        None => args.to_vec(),
+    }
+}
+
+/// Prints a type on a single line.
+///
+fn print(printer: &mut Printer<'_>, typ: &typ::Type) -> String {
+    pretty::format(std::isize::MAX, printer.to_doc(typ))
+}
@@ -0,0 +1,539 @@
+use super::*;
+
+#[test]
+fn render_readme_test() {
+    let github = Repository::GitHub {
+        user: "someone".to_string(),
+        repo: "my_lib".to_string(),
+    };
+    let links = PageLinks {
+        pages: vec![
+            ("README.md".to_string(), "".to_string()),
+            ("docs/guide.md".to_string(), "docs/guide".to_string()),
+        ]
+        .into_iter()
+        .collect(),
+        repository: &github,
+        version: "1.2.0",
+    };
+    let mut assets = vec![];
+    assert_eq!(
+        render_readme(
+            r##"# my_lib
+
+See the [guide](docs/guide.md#start), [Gleam](https://gleam.run),
+[usage](#usage), [home](/index.html), and the [licence](./LICENSE).
+
+![Logo](images/logo.png)
+
+```rust
+fn main() {}
+```
+"##,
+            "README.md",
+            ".",
+            &links,
+            &mut assets,
+        ),
+        r##"<h1>my_lib</h1>
+<p>See the <a href="./docs/guide/#start">guide</a>, <a href="https://gleam.run">Gleam</a>,
+<a href="#usage">usage</a>, <a href="/index.html">home</a>, and the <a href="https://github.com/someone/my_lib/blob/v1.2.0/LICENSE">licence</a>.</p>
+<p><img src="./images/logo.png" alt="Logo" /></p>
+<pre><code class="language-rust">fn main() {}
+</code></pre>
+"##
+    );
+    assert_eq!(vec!["images/logo.png"], assets);
+
+    // Links in pages are relative to their markdown file, and those outside
+    // of the project are left as they are
+    let mut assets = vec![];
+    assert_eq!(
+        render_readme(
+            "[Home](../README.md) [Up](../../up.md) ![Chart](img/chart.svg?v=1)",
+            "docs/guide.md",
+            "../..",
+            &links,
+            &mut assets,
+        ),
+        r#"<p><a href="../../">Home</a> <a href="../../up.md">Up</a> <img src="../../docs/img/chart.svg?v=1" alt="Chart" /></p>
+"#
+    );
+    assert_eq!(vec!["docs/img/chart.svg"], assets);
+
+    // Without a known repository, links to other files are left as they are
+    let links = PageLinks {
+        repository: &Repository::None,
+        ..links
+    };
+    assert_eq!(
+        render_readme("[licence](LICENSE)", "README.md", ".", &links, &mut vec![]),
+        "<p><a href=\"LICENSE\">licence</a></p>\n"
+    );
+}
+
+#[test]
+fn is_relative_url_test() {
+    assert!(is_relative_url("docs/guide.md"));
+    assert!(is_relative_url("./logo.png"));
+    assert!(is_relative_url("../other/README.md"));
+    assert!(!is_relative_url(""));
+    assert!(!is_relative_url("#usage"));
+    assert!(!is_relative_url("/index.html"));
+    assert!(!is_relative_url("//example.com/logo.png"));
+    assert!(!is_relative_url("https://gleam.run"));
+    assert!(!is_relative_url("mailto:me@example.com"));
+}
+
+#[test]
+fn project_path_test() {
+    assert_eq!(
+        Some("docs/guide.md".to_string()),
+        project_path("README.md", "docs/guide.md")
+    );
+    assert_eq!(
+        Some("docs/img/logo.png".to_string()),
+        project_path("docs/guide.md", "./img//logo.png")
+    );
+    assert_eq!(
+        Some("README.md".to_string()),
+        project_path("docs/guides/start.md", "../../README.md")
+    );
+    assert_eq!(Some("docs".to_string()), project_path("docs/a/b.md", ".."));
+    assert_eq!(None, project_path("docs/guide.md", "../../other/README.md"));
+}
+
+fn analyse(src: &str) -> Vec<Analysed> {
+    analyse_modules(vec![("/src", "pets", src, ModuleOrigin::Src)])
+}
+
+fn analyse_modules(modules: Vec<(&str, &str, &str, ModuleOrigin)>) -> Vec<Analysed> {
+    crate::project::Cache::default()
+        .analysed(
+            modules
+                .into_iter()
+                .map(|(base, name, src, origin)| crate::project::Input {
+                    source_base_path: PathBuf::from(base),
+                    path: PathBuf::from(format!("{}/{}.gleam", base, name)),
+                    src: src.to_string(),
+                    origin,
+                })
+                .collect(),
+        )
+        .unwrap()
+}
+
+#[test]
+fn type_constructors_test() {
+    let analysed = analyse(
+        r#"
+/// A pet
+pub type Pet {
+  /// A cat, with a name
+  Cat(name: String, lives: Int)
+  Dog
+  /// Not sure
+  Other(String)
+}
+
+pub external type Leash
+
+pub type Pets =
+  List(Pet)
+
+type Hidden {
+  Hidden
+}
+"#,
+    );
+    let types: Vec<_> = analysed[0].ast.statements.iter().flat_map(type_).collect();
+
+    assert_eq!(
+        types
+            .iter()
+            .map(|t| (t.name, t.documentation.as_str(), t.constructors.len()))
+            .collect::<Vec<_>>(),
+        vec![
+            ("Pet", "<p>A pet</p>\n", 3),
+            ("Leash", "", 0),
+            ("Pets", "", 0)
+        ]
+    );
+    assert_eq!(
+        types[0]
+            .constructors
+            .iter()
+            .map(|c| (c.definition.as_str(), c.documentation.as_str()))
+            .collect::<Vec<_>>(),
+        vec![
+            (
+                "Cat(name: String, lives: Int)",
+                "<p>A cat, with a name</p>\n"
+            ),
+            ("Dog", ""),
+            ("Other(String)", "<p>Not sure</p>\n"),
+        ]
+    );
+}
+
+#[test]
+fn function_type_links_test() {
+    let analysed = analyse_modules(vec![
+        (
+            "/app/_build/default/lib/gleam_stdlib/src",
+            "gleam/option",
+            "pub type Option(a) { Some(a) None }",
+            ModuleOrigin::Dependency,
+        ),
+        (
+            "/elsewhere/my_pets_lib/src",
+            "pets/food",
+            "pub type Food { Food }",
+            ModuleOrigin::Dependency,
+        ),
+        (
+            "/src",
+            "pets/db",
+            "pub type DbError { DbError }",
+            ModuleOrigin::Src,
+        ),
+        (
+            "/src",
+            "pets",
+            r#"
+import pets/db
+import pets/food
+import gleam/option.{Option}
+
+pub type User {
+  User(name: String)
+}
+
+pub fn find(id, d: Option(User)) -> Result(User, db.DbError) {
+  Error(db.DbError)
+}
+
+pub external fn size(List(a)) -> Int = "erlang" "length"
+
+pub fn feed(f: food.Food) -> Nil {
+  Nil
+}
+"#,
+            ModuleOrigin::Src,
+        ),
+    ]);
+    let module = analysed.iter().find(|m| m.name == ["pets"]).unwrap();
+    let type_links = TypeLinks::new(&analysed, Path::new("/app"));
+    let links = |m: &[String], n: &str| type_links.url(&module.name, m, n);
+    let signatures: Vec<_> = module
+        .ast
+        .statements
+        .iter()
+        .flat_map(|s| function(s, &module.ast.type_info, &links))
+        .map(|f| f.signature)
+        .collect();
+
+    assert_eq!(
+        signatures,
+        vec![
+            "pub fn find(id: a, d: <a href=\"https://hexdocs.pm/gleam_stdlib/gleam/option/#Option\">Option</a>(<a href=\"#User\">User</a>)) -> Result(<a href=\"#User\">User</a>, <a href=\"../pets/db/#DbError\">DbError</a>)",
+            "pub external fn size(List(a)) -> Int",
+            // Path dependencies are not on HexDocs
+            "pub fn feed(f: Food) -> Nil",
+        ]
+    );
+}
+
+#[test]
+fn search_items_test() {
+    let analysed = analyse(
+        r#"
+//// Pets and their owners.
+////
+//// More detail.
+
+/// A pet
+///
+/// More detail.
+pub type Pet {
+  /// A cat
+  Cat(name: String)
+  Dog
+}
+
+/// Says hello
+pub fn greet(pet: Pet) -> String {
+  "Hello"
+}
+
+fn private() {
+  Nil
+}
+"#,
+    );
+    let item = |kind, name: &str, signature: &str, doc: &str, url: &str| SearchItem {
+        kind,
+        module: "pets".to_string(),
+        name: name.to_string(),
+        signature: signature.to_string(),
+        doc: doc.to_string(),
+        url: url.to_string(),
+    };
+
+    assert_eq!(
+        search_items(&analysed[0]),
+        vec![
+            item("module", "pets", "", "Pets and their owners.", "pets/"),
+            item(
+                "type",
+                "Pet",
+                "pub type Pet {\n  Cat(name: String)\n  Dog\n}",
+                "A pet",
+                "pets/#Pet"
+            ),
+            item(
+                "constructor",
+                "Cat",
+                "Cat(name: String)",
+                "A cat",
+                "pets/#Pet"
+            ),
+            item("constructor", "Dog", "Dog", "", "pets/#Pet"),
+            item(
+                "function",
+                "greet",
+                "pub fn greet(pet: Pet) -> String",
+                "Says hello",
+                "pets/#greet"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn source_url_test() {
+    let analysed = analyse(
+        r#"/// A pet
+pub type Pet {
+  Cat
+  Dog(
+    name: String,
+  )
+}
+
+pub type Pets =
+  List(Pet)
+
+pub fn greet(pet: Pet) -> String {
+  "Hello"
+}
+"#,
+    );
+    let module = &analysed[0];
+    let url = |statement| {
+        source_url(
+            "https://example.com/{version}/{path}#{line_start}-{line_end}",
+            "1.2.0",
+            Path::new("/"),
+            module,
+            statement,
+        )
+    };
+
+    assert_eq!(
+        module.ast.statements.iter().map(url).collect::<Vec<_>>(),
+        vec![
+            "https://example.com/1.2.0/src/pets.gleam#2-7",
+            "https://example.com/1.2.0/src/pets.gleam#9-10",
+            "https://example.com/1.2.0/src/pets.gleam#12-14",
+        ]
+    );
+}
+
+#[test]
+fn extra_pages_test() {
+    let config = crate::config::parse(
+        r#"
+name = "pets"
+
+repository = { type = "github", user = "someone", repo = "pets" }
+
+[[docs.pages]]
+title = "Getting started"
+path = "docs/guides/start.md"
+"#,
+        &PathBuf::from("/app/gleam.toml"),
+    )
+    .unwrap();
+    let pages = vec![Page {
+        title: "Getting started".to_string(),
+        path: "docs/guides/start.md".to_string(),
+        text: "See [the readme](../../README.md) and [feeding](feeding.md).\n\n![Cat](cat.png)"
+            .to_string(),
+    }];
+    let mut files = vec![];
+    let mut copied = vec![];
+    generate_html(
+        &config,
+        &analyse("pub fn pet() { 1 }"),
+        "# pets\n\n![Cat](docs/guides/cat.png) [Start](docs/guides/start.md)",
+        &pages,
+        Path::new("/app"),
+        &mut files,
+        &mut copied,
+        &PathBuf::from("/app/doc"),
+    );
+
+    // Links point at the pages, other files of the project in the
+    // repository, and images at copies within the documentation
+    let page = files
+        .iter()
+        .find(|f| f.path == PathBuf::from("/app/doc/docs/guides/start/index.html"))
+        .unwrap();
+    assert!(page.text.contains(
+        r#"<p>See <a href="../../../">the readme</a> and <a href="https://github.com/someone/pets/blob/v1.0.0/docs/guides/feeding.md">feeding</a>.</p>
+<p><img src="../../../docs/guides/cat.png" alt="Cat" /></p>"#
+    ));
+    assert!(page
+        .text
+        .contains(r#"<link rel="stylesheet" href="..&#x2f;..&#x2f;../index.css""#));
+
+    // Every page links to the extra pages in its navigation
+    let index = files
+        .iter()
+        .find(|f| f.path == PathBuf::from("/app/doc/index.html"))
+        .unwrap();
+    assert!(index
+        .text
+        .contains(r#"<a href="./docs&#x2f;guides&#x2f;start">Getting started</a>"#));
+    assert!(index.text.contains(
+        r#"<img src="./docs/guides/cat.png" alt="Cat" /> <a href="./docs/guides/start/">Start</a>"#
+    ));
+
+    // Each image is copied once
+    assert_eq!(
+        vec![CopiedFile {
+            from: PathBuf::from("/app/docs/guides/cat.png"),
+            to: PathBuf::from("/app/doc/docs/guides/cat.png"),
+        }],
+        copied
+    );
+}
+
+#[test]
+fn json_test() {
+    let config = crate::config::parse(
+        "name = \"pets\"\nversion = \"1.2.0\"\n",
+        &PathBuf::from("/gleam.toml"),
+    )
+    .unwrap();
+    let analysed = analyse_modules(vec![
+        (
+            "/test",
+            "pets_test",
+            "pub fn pets_test() { 1 }",
+            ModuleOrigin::Test,
+        ),
+        (
+            "/src",
+            "pets",
+            r#"
+//// Pets and their owners.
+
+/// A pet
+pub type Pet(owner) {
+  /// A cat
+  Cat(name: String, owner: owner)
+  Stray
+}
+
+pub external type Leash
+
+pub type Pets(owner) =
+  List(Pet(owner))
+
+/// Finds a pet
+pub fn find(in pets, name _) -> Result(Pet(o), Nil) {
+  case pets {
+    [pet, .._] -> Ok(pet)
+    [] -> Error(Nil)
+  }
+}
+
+pub external fn count(List(a)) -> Int = "erlang" "length"
+
+fn private() {
+  Nil
+}
+"#,
+            ModuleOrigin::Src,
+        ),
+    ]);
+
+    assert_eq!(
+        serde_json::to_value(json::package(&config, &analysed)).unwrap(),
+        serde_json::json!({
+            "name": "pets",
+            "version": "1.2.0",
+            "modules": [{
+                "name": "pets",
+                "documentation": " Pets and their owners.",
+                "types": [
+                    {
+                        "kind": "custom",
+                        "name": "Pet",
+                        "parameters": ["a"],
+                        "documentation": " A pet",
+                        "constructors": [
+                            {
+                                "name": "Cat",
+                                "documentation": " A cat",
+                                "parameters": [
+                                    {"label": "name", "type": "String"},
+                                    {"label": "owner", "type": "a"},
+                                ],
+                            },
+                            {
+                                "name": "Stray",
+                                "documentation": null,
+                                "parameters": [],
+                            },
+                        ],
+                    },
+                    {
+                        "kind": "external",
+                        "name": "Leash",
+                        "parameters": [],
+                        "documentation": null,
+                    },
+                    {
+                        "kind": "alias",
+                        "name": "Pets",
+                        "parameters": ["a"],
+                        "documentation": null,
+                        "alias": "List(Pet(a))",
+                    },
+                ],
+                "functions": [
+                    {
+                        "name": "find",
+                        "documentation": " Finds a pet",
+                        "external": false,
+                        "parameters": [
+                            {"label": "in", "type": "List(Pet(a))"},
+                            {"label": "name", "type": "b"},
+                        ],
+                        "return_type": "Result(Pet(a), Nil)",
+                    },
+                    {
+                        "name": "count",
+                        "documentation": null,
+                        "external": true,
+                        "parameters": [{"label": null, "type": "List(a)"}],
+                        "return_type": "Int",
+                    },
+                ],
+            }],
+        })
+    );
+}
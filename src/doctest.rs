@@ -0,0 +1,277 @@
+#[cfg(test)]
+mod tests;
+
+use crate::error::Error;
+use crate::project::{Input, ModuleOrigin};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// The suffix added to the name of a module to name the test module
+/// generated from the code examples in its doc comments.
+const MODULE_SUFFIX: &str = "_doctest";
+
+/// Generates a test module for each module in the source directories that
+/// has Gleam code examples in its doc comments, so that the examples are
+/// type checked along with the rest of the project.
+///
+/// Each example becomes a test function, run by EUnit like any other test,
+/// within a module that imports the documented module. Lines of the test
+/// module are kept at the same line and column as in the documented module,
+/// so that errors in the examples can be reported against the doc comments
+/// with `locate_error` and `documented`.
+///
+pub fn inputs(inputs: &[Input]) -> Vec<Input> {
+    inputs
+        .iter()
+        .filter(|input| input.origin == ModuleOrigin::Src)
+        .filter_map(|input| {
+            let module = input
+                .path
+                .strip_prefix(&input.source_base_path)
+                .ok()?
+                .with_extension("")
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().to_string())
+                .collect::<Vec<_>>()
+                .join("/");
+            Some(Input {
+                source_base_path: input.source_base_path.clone(),
+                path: doctest_path(&input.path),
+                src: module_source(&module, &input.src)?,
+                origin: ModuleOrigin::Test,
+            })
+        })
+        .collect()
+}
+
+/// The source of the test module for the code examples of a module, or
+/// nothing if it has no examples.
+///
+/// Examples are the fenced code blocks marked as `gleam` within `///` doc
+/// comments. Any imports at the start of an example are imported by the
+/// test module, and the rest of the example becomes the body of a test
+/// function.
+///
+pub fn module_source(module: &str, src: &str) -> Option<String> {
+    let mut lines: Vec<String> = vec![];
+    let mut imports = HashSet::new();
+    imports.insert(format!("import {}", module));
+    let mut examples = 0;
+    let mut state = State::Text;
+
+    for line in src.split('\n') {
+        let trimmed = line.trim_start();
+        let content = if trimmed.starts_with("///") && !trimmed.starts_with("////") {
+            Some((line.len() - trimmed.len() + 3, &trimmed[3..]))
+        } else {
+            None
+        };
+
+        let (column, content) = match (content, state) {
+            (Some(content), _) => content,
+
+            // The doc comment ended without closing the example
+            (None, State::Example { started: true, .. }) => {
+                state = State::Text;
+                lines.push("}".to_string());
+                continue;
+            }
+
+            (None, _) => {
+                state = State::Text;
+                lines.push(String::new());
+                continue;
+            }
+        };
+        let is_fence = content.trim().starts_with("```");
+
+        match state {
+            State::Text if is_fence && content.trim()[3..].trim() == "gleam" => {
+                if examples == 0 {
+                    lines.push(format!("import {}", module));
+                } else {
+                    lines.push(String::new());
+                }
+                examples += 1;
+                state = State::Example {
+                    head: lines.len() - 1,
+                    started: false,
+                };
+            }
+
+            State::Text if is_fence => {
+                state = State::OtherCode;
+                lines.push(String::new());
+            }
+
+            State::OtherCode if is_fence => {
+                state = State::Text;
+                lines.push(String::new());
+            }
+
+            State::Text | State::OtherCode => lines.push(String::new()),
+
+            State::Example { started, .. } if is_fence => {
+                if started {
+                    lines.push("}".to_string());
+                } else {
+                    lines.push(String::new());
+                }
+                state = State::Text;
+            }
+
+            State::Example { started: false, .. } if content.trim().starts_with("import ") => {
+                // Modules imported by more than one example are only
+                // imported once
+                if imports.insert(content.trim().to_string()) {
+                    lines.push(format!("{}{}", " ".repeat(column), content));
+                } else {
+                    lines.push(String::new());
+                }
+                state = State::Example {
+                    head: lines.len() - 1,
+                    started: false,
+                };
+            }
+
+            State::Example { started: false, .. } if content.trim().is_empty() => {
+                lines.push(String::new());
+            }
+
+            State::Example { head, started } => {
+                if !started {
+                    lines[head].push_str(&format!(" pub fn doctest_{}_test() {{", examples));
+                }
+                lines.push(format!("{}{}", " ".repeat(column), content));
+                state = State::Example {
+                    head,
+                    started: true,
+                };
+            }
+        }
+    }
+
+    if let State::Example { started: true, .. } = state {
+        lines.push("}".to_string());
+    }
+
+    // Lines shorter than in the documented module are padded so that, other
+    // than after the heads of test functions, byte offsets match too
+    for (line, documented) in lines.iter_mut().zip(src.split('\n')) {
+        if line.len() < documented.len() {
+            line.push_str(&" ".repeat(documented.len() - line.len()));
+        }
+    }
+
+    if examples == 0 {
+        None
+    } else {
+        Some(lines.join("\n"))
+    }
+}
+
+/// Where the lines of a doc comment are, while generating a test module.
+///
+#[derive(Debug, Clone, Copy)]
+enum State {
+    Text,
+    /// Within a code block that is not a Gleam example.
+    OtherCode,
+    /// Within a Gleam example. `head` is the line the test function is
+    /// declared on, and `started` is whether the body has begun.
+    Example {
+        head: usize,
+        started: bool,
+    },
+}
+
+/// The path and source of the documented module of each generated test
+/// module, keyed by the path of the test module.
+///
+pub type Documented = HashMap<PathBuf, (PathBuf, String)>;
+
+/// The documented modules of the given test modules, as generated by
+/// `inputs`, for use when reporting errors with `locate_error`.
+///
+pub fn documented(inputs: &[Input], doctests: &[Input]) -> Documented {
+    doctests
+        .iter()
+        .filter_map(|doctest| {
+            let path = documented_path(&doctest.path);
+            let input = inputs.iter().find(|input| input.path == path)?;
+            let src = aligned_source(&input.src, &doctest.src);
+            Some((doctest.path.clone(), (path, src)))
+        })
+        .collect()
+}
+
+/// The source of a documented module with each line padded to the length of
+/// the same line of its test module, so that locations within the test
+/// module are at the same byte offsets in the returned source.
+///
+fn aligned_source(documented: &str, generated: &str) -> String {
+    let mut documented = documented.split('\n');
+    generated
+        .split('\n')
+        .map(|line| {
+            let mut aligned = documented.next().unwrap_or("").to_string();
+            if aligned.len() < line.len() {
+                aligned.push_str(&" ".repeat(line.len() - aligned.len()));
+            }
+            aligned
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Errors in test modules generated from code examples are reported against
+/// the modules that the examples are from. As the lines of the generated
+/// modules are at the same line and column as the examples, only the path
+/// and source need to be changed.
+///
+pub fn locate_error(error: Error, documented: &Documented) -> Error {
+    let locate = |path: PathBuf, src: String| match documented.get(&path) {
+        Some((path, src)) => (path.clone(), src.clone()),
+        None => (path, src),
+    };
+    match error {
+        Error::Parse { path, src, error } => {
+            let (path, src) = locate(path, src);
+            Error::Parse { path, src, error }
+        }
+        Error::Type { path, src, error } => {
+            let (path, src) = locate(path, src);
+            Error::Type { path, src, error }
+        }
+        Error::UnknownImport {
+            module,
+            import,
+            location,
+            path,
+            src,
+            modules,
+        } => {
+            let (path, src) = locate(path, src);
+            Error::UnknownImport {
+                module,
+                import,
+                location,
+                path,
+                src,
+                modules,
+            }
+        }
+        error => error,
+    }
+}
+
+fn doctest_path(path: &Path) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!("{}{}.gleam", stem, MODULE_SUFFIX))
+}
+
+fn documented_path(path: &Path) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let stem = &stem[..stem.len().saturating_sub(MODULE_SUFFIX.len())];
+    path.with_file_name(format!("{}.gleam", stem))
+}
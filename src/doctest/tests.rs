@@ -0,0 +1,148 @@
+use super::*;
+
+/// The source of a test module without the padding at the end of its lines.
+///
+fn trimmed(src: Option<String>) -> Option<String> {
+    src.map(|src| {
+        src.split('\n')
+            .map(str::trim_end)
+            .collect::<Vec<_>>()
+            .join("\n")
+    })
+}
+
+#[test]
+fn module_source_test() {
+    assert_eq!(
+        None,
+        module_source("pets", "/// No examples\npub fn a() { 1 }\n")
+    );
+
+    assert_eq!(
+        trimmed(module_source(
+            "pets",
+            r#"//// ```gleam
+//// 1
+//// ```
+
+/// Greets a pet
+///
+/// ```gleam
+/// import gleam/string
+///
+/// pets.greet("Tom")
+/// |> string.length
+/// ```
+///
+/// ```erlang
+/// pets:greet(<<"Tom">>).
+/// ```
+pub fn greet(name) {
+  "Hello"
+}
+
+  /// ```gleam
+  /// import gleam/string
+  /// import pets
+  /// let x = pets.greet("Tom")
+  /// x
+pub fn other() { 1 }
+"#
+        )),
+        Some(
+            r#"
+
+
+
+
+
+import pets
+    import gleam/string pub fn doctest_1_test() {
+
+    pets.greet("Tom")
+    |> string.length
+}
+
+
+
+
+
+
+
+
+
+
+ pub fn doctest_2_test() {
+      let x = pets.greet("Tom")
+      x
+}
+"#
+            .to_string()
+        )
+    );
+}
+
+#[test]
+fn inputs_test() {
+    let input = |path: &str, src: &str, origin| Input {
+        source_base_path: PathBuf::from("/src"),
+        path: PathBuf::from(path),
+        src: src.to_string(),
+        origin,
+    };
+    let example = "/// ```gleam\n/// 1\n/// ```\n";
+
+    assert_eq!(
+        inputs(&[
+            input("/src/pets/db.gleam", example, ModuleOrigin::Src),
+            input("/src/pets.gleam", "pub fn a() { 1 }", ModuleOrigin::Src),
+            input("/src/pets_test.gleam", example, ModuleOrigin::Test),
+        ]),
+        vec![input(
+            "/src/pets/db_doctest.gleam",
+            "import pets/db pub fn doctest_1_test() {\n    1\n}      \n",
+            ModuleOrigin::Test
+        )]
+    );
+}
+
+#[test]
+fn locate_error_test() {
+    let pets = "pub fn greet(name: String) { name }\n\n/// ```gleam\n/// pets.greet(1)\n/// ```\npub fn a() { 1 }\n";
+    let srcs = vec![Input {
+        source_base_path: PathBuf::from("/src"),
+        path: PathBuf::from("/src/pets.gleam"),
+        src: pets.to_string(),
+        origin: ModuleOrigin::Src,
+    }];
+    let doctests = inputs(&srcs);
+    let documented = documented(&srcs, &doctests);
+    let error = crate::project::Cache::default()
+        .analysed(srcs.into_iter().chain(doctests).collect())
+        .map_err(|e| locate_error(e, &documented))
+        .unwrap_err();
+
+    // The snippet shown is of the doc comment
+    let mut buffer = termcolor::Buffer::no_color();
+    error.pretty(&mut buffer);
+    let rendered = String::from_utf8(buffer.into_inner()).unwrap();
+    assert!(rendered.contains("/src/pets.gleam:4:16"), "{}", rendered);
+    assert!(rendered.contains("/// pets.greet(1)"), "{}", rendered);
+
+    match error {
+        Error::Type { path, src, error } => {
+            assert_eq!(path, PathBuf::from("/src/pets.gleam"));
+            assert_eq!(trimmed(Some(src.clone())), trimmed(Some(pets.to_string())));
+            let location = match error {
+                crate::typ::Error::CouldNotUnify { location, .. } => location,
+                other => panic!("expected a unify error, got {:?}", other),
+            };
+            // The error is at the same line and column as in the doc comment
+            let line = src[..location.start].matches('\n').count();
+            let column = location.start - src[..location.start].rfind('\n').unwrap() - 1;
+            assert_eq!((line, column), (3, 15));
+            assert_eq!(&src[location.start..location.end], "1");
+        }
+        other => panic!("expected a type error, got {:?}", other),
+    }
+}
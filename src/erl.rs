@@ -3,6 +3,7 @@ mod tests;
 
 use crate::{
     ast::*,
+    config::ProjectConfig,
     error::GleamExpect,
     pretty::*,
     typ::{ModuleValueConstructor, PatternConstructor, ValueConstructor, ValueConstructorVariant},
@@ -88,6 +89,57 @@ pub fn record_definition(name: &str, fields: &[&str]) -> String {
     buffer
 }
 
+/// The `.app` resource file for an OTP application, which lists the modules
+/// of the application and the other applications it depends upon.
+///
+pub fn application(config: &ProjectConfig, modules: &[String]) -> String {
+    let mut applications = vec!["kernel", "stdlib"];
+    for name in config
+        .dependencies
+        .keys()
+        .chain(config.erlang.extra_applications.iter())
+    {
+        if !applications.contains(&name.as_str()) {
+            applications.push(name);
+        }
+    }
+
+    let start_module = match &config.erlang.application_start_module {
+        Some(module) => format!(
+            "\n    {{mod, {{{}, []}}}},",
+            atom(module.clone()).format(80)
+        ),
+        None => "".to_string(),
+    };
+
+    format!(
+        r#"{{application, {}, [
+    {{vsn, "{}"}},
+    {{description, "{}"}},
+    {{modules, [{}]}},
+    {{registered, []}},{}
+    {{applications, [{}]}}
+]}}.
+"#,
+        atom(config.name.clone()).format(80),
+        erlang_string(&config.version),
+        erlang_string(&config.description),
+        atom_list(modules.iter().map(String::as_str)),
+        start_module,
+        atom_list(applications.into_iter()),
+    )
+}
+
+fn atom_list<'a>(names: impl Iterator<Item = &'a str>) -> String {
+    names
+        .map(|name| atom(name.to_string()).format(80))
+        .join(", ")
+}
+
+fn erlang_string(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
 pub fn module(module: &TypedModule) -> String {
     let module_name = module.name.as_slice();
     let exports = concat(
@@ -245,6 +297,7 @@ fn seq(first: &TypedExpr, then: &TypedExpr, env: &mut Env) -> Document {
 // TODO: Surround left or right in parens if required
 fn bin_op(name: &BinOp, left: &TypedExpr, right: &TypedExpr, env: &mut Env) -> Document {
     let op = match name {
+        BinOp::Concatenate => return string_concatenate(left, right, env),
         BinOp::And => "andalso",
         BinOp::Or => "orelse",
         BinOp::LtInt | BinOp::LtFloat => "<",
@@ -271,6 +324,72 @@ fn bin_op(name: &BinOp, left: &TypedExpr, right: &TypedExpr, env: &mut Env) -> D
         .append(expr(right, env))
 }
 
+fn string_concatenate(left: &TypedExpr, right: &TypedExpr, env: &mut Env) -> Document {
+    let mut segments = vec![];
+    string_concatenate_segments(left, &mut segments, env);
+    string_concatenate_segments(right, &mut segments, env);
+    concat(segments.into_iter().intersperse(delim(",")))
+        .nest_current()
+        .surround("<<", ">>")
+        .group()
+}
+
+// Nested concatenations are flattened into a single binary so that `a <> b <> c`
+// does not build an intermediate binary for `a <> b`.
+fn string_concatenate_segments(
+    expression: &TypedExpr,
+    segments: &mut Vec<Document>,
+    env: &mut Env,
+) {
+    match expression {
+        TypedExpr::BinOp {
+            name: BinOp::Concatenate,
+            left,
+            right,
+            ..
+        } => {
+            string_concatenate_segments(left, segments, env);
+            string_concatenate_segments(right, segments, env);
+        }
+
+        TypedExpr::String { value, .. } => {
+            segments.push(value.as_str().to_doc().surround("\"", "\"/utf8"))
+        }
+
+        TypedExpr::Var {
+            constructor:
+                ValueConstructor {
+                    variant: ValueConstructorVariant::LocalVariable,
+                    ..
+                },
+            ..
+        } => segments.push(expr(expression, env).append("/binary")),
+
+        _ => segments.push(expr(expression, env).surround("(", ")").append("/binary")),
+    }
+}
+
+fn unary_op(name: &UnaryOp, value: &TypedExpr, env: &mut Env) -> Document {
+    let op = match name {
+        UnaryOp::NegateInt | UnaryOp::NegateFloat => "-",
+        UnaryOp::Not => "not ",
+    };
+
+    // Operands that are themselves operators or negative literals are wrapped
+    // in parens so that `- -1` is not printed as the `--` operator.
+    let value = match value {
+        TypedExpr::BinOp { .. } | TypedExpr::UnaryOp { .. } => expr(value, env).surround("(", ")"),
+        TypedExpr::Int { value: v, .. } | TypedExpr::Float { value: v, .. }
+            if v.starts_with('-') =>
+        {
+            expr(value, env).surround("(", ")")
+        }
+        _ => wrap_expr(value, env),
+    };
+
+    op.to_doc().append(value)
+}
+
 fn pipe(value: &TypedExpr, fun: &TypedExpr, env: &mut Env) -> Document {
     let arg = CallArg {
         label: None,
@@ -305,12 +424,19 @@ fn pattern(p: &TypedPattern, env: &mut Env) -> Document {
 
         Pattern::Var { name, .. } => env.next_local_var_name(name.to_string()),
 
-        Pattern::Int { value, .. } => value.as_str().to_doc(),
+        Pattern::Int { value, .. } => int(value),
 
         Pattern::Float { value, .. } => float(value.as_ref()),
 
         Pattern::String { value, .. } => string(value),
 
+        Pattern::StringPrefix { prefix, rest, .. } => prefix
+            .as_str()
+            .to_doc()
+            .surround("<<\"", "\"/utf8, ")
+            .append(pattern(rest, env))
+            .append("/binary>>"),
+
         Pattern::Constructor {
             args,
             constructor: PatternConstructor::Record { name },
@@ -321,11 +447,31 @@ fn pattern(p: &TypedPattern, env: &mut Env) -> Document {
     }
 }
 
+fn int(value: &str) -> Document {
+    let value = value.replace("_", "");
+    let (sign, value) = if value.starts_with('-') {
+        ("-", &value[1..])
+    } else {
+        ("", value.as_str())
+    };
+    match value.get(0..2) {
+        Some("0x") => format!("{}16#{}", sign, &value[2..]).to_doc(),
+        Some("0o") => format!("{}8#{}", sign, &value[2..]).to_doc(),
+        Some("0b") => format!("{}2#{}", sign, &value[2..]).to_doc(),
+        _ => format!("{}{}", sign, value).to_doc(),
+    }
+}
+
 fn float(value: &str) -> Document {
-    if value.ends_with(".") {
-        format!("{}0", value).to_doc()
+    let value = value.replace("_", "");
+    let (mantissa, exponent) = match value.find(|c| c == 'e' || c == 'E') {
+        Some(i) => value.split_at(i),
+        None => (value.as_str(), ""),
+    };
+    if mantissa.ends_with('.') {
+        format!("{}0{}", mantissa, exponent).to_doc()
     } else {
-        value.to_string().to_doc()
+        value.to_doc()
     }
 }
 
@@ -534,7 +680,34 @@ fn bare_clause_guard(guard: &TypedClauseGuard, env: &mut Env) -> Document {
             .append(" =< ")
             .append(clause_guard(right.as_ref(), env)),
 
-        ClauseGuard::Int { value, .. } => value.to_string().to_doc(),
+        ClauseGuard::Int { value, .. } => int(value),
+
+        ClauseGuard::Float { value, .. } => float(value),
+
+        ClauseGuard::String { value, .. } => string(value),
+
+        ClauseGuard::Tuple { elems, .. } => tuple(elems.iter().map(|e| bare_clause_guard(e, env))),
+
+        ClauseGuard::Constructor { name, args, .. } if args.is_empty() => {
+            atom(name.to_snake_case())
+        }
+
+        ClauseGuard::Constructor { name, args, .. } => tuple(
+            std::iter::once(atom(name.to_snake_case()))
+                .chain(args.iter().map(|arg| bare_clause_guard(&arg.value, env))),
+        ),
+
+        ClauseGuard::TupleIndex { tuple, index, .. } => {
+            tuple_index_inline(bare_clause_guard(tuple, env), *index)
+        }
+
+        // Records are tagged tuples so the first element is skipped
+        ClauseGuard::FieldAccess {
+            container, index, ..
+        } => tuple_index_inline(
+            bare_clause_guard(container, env),
+            index.gleam_expect("Erl printing: guard field access index") + 1,
+        ),
 
         // Only local variables are supported and the typer ensures that all
         // ClauseGuard::Vars are local variables
@@ -562,7 +735,14 @@ fn clause_guard(guard: &TypedClauseGuard, env: &mut Env) -> Document {
             .append(")"),
 
         // Values are not wrapped
-        ClauseGuard::Var { .. } | ClauseGuard::Int { .. } => bare_clause_guard(guard, env),
+        ClauseGuard::Var { .. }
+        | ClauseGuard::Int { .. }
+        | ClauseGuard::Float { .. }
+        | ClauseGuard::String { .. }
+        | ClauseGuard::Tuple { .. }
+        | ClauseGuard::Constructor { .. }
+        | ClauseGuard::TupleIndex { .. }
+        | ClauseGuard::FieldAccess { .. } => bare_clause_guard(guard, env),
     }
 }
 
@@ -712,7 +892,7 @@ fn expr(expression: &TypedExpr, env: &mut Env) -> Document {
     match expression {
         TypedExpr::ListNil { .. } => "[]".to_doc(),
         TypedExpr::Todo { .. } => "erlang:error({gleam_error, todo})".to_doc(),
-        TypedExpr::Int { value, .. } => value.as_str().to_doc(),
+        TypedExpr::Int { value, .. } => int(value),
         TypedExpr::Float { value, .. } => float(value.as_ref()),
         TypedExpr::String { value, .. } => string(value),
         TypedExpr::Seq { first, then, .. } => seq(first, then, env),
@@ -760,14 +940,20 @@ fn expr(expression: &TypedExpr, env: &mut Env) -> Document {
             name, left, right, ..
         } => bin_op(&name, left, right, env),
 
+        TypedExpr::UnaryOp { name, value, .. } => unary_op(name, value, env),
+
         TypedExpr::Tuple { elems, .. } => tuple(elems.into_iter().map(|e| wrap_expr(e, env))),
     }
 }
 
 fn tuple_index(tuple: &TypedExpr, index: u64, env: &mut Env) -> Document {
+    let tuple_doc = expr(tuple, env);
+    tuple_index_inline(tuple_doc, index)
+}
+
+fn tuple_index_inline(tuple_doc: Document, index: u64) -> Document {
     use std::iter::once;
     let index_doc = format!("{}", (index + 1)).to_doc();
-    let tuple_doc = expr(tuple, env);
     let iter = once(index_doc).chain(once(tuple_doc));
     "erlang:element".to_doc().append(wrap_args(iter))
 }
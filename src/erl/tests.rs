@@ -10,6 +10,54 @@ fn record_definition_test() {
     );
 }
 
+#[test]
+fn application_test() {
+    let path = std::path::PathBuf::from("/gleam.toml");
+    let config = crate::config::parse(
+        r#"
+name = "my_app"
+version = "1.2.0"
+description = "It's \"great\""
+
+[dependencies]
+gleam_stdlib = "~> 0.8"
+stdlib = "~> 1.0"
+
+[erlang]
+application_start_module = "my_app_sup"
+extra_applications = ["inets", "gleam_stdlib"]
+"#,
+        &path,
+    )
+    .unwrap();
+
+    assert_eq!(
+        application(&config, &["my_app".to_string(), "my_app@Web".to_string()]),
+        r#"{application, my_app, [
+    {vsn, "1.2.0"},
+    {description, "It's \"great\""},
+    {modules, [my_app, 'my_app@Web']},
+    {registered, []},
+    {mod, {my_app_sup, []}},
+    {applications, [kernel, stdlib, gleam_stdlib, inets]}
+]}.
+"#
+    );
+
+    let config = crate::config::parse(r#"name = "my_lib""#, &path).unwrap();
+    assert_eq!(
+        application(&config, &[]),
+        r#"{application, my_lib, [
+    {vsn, "1.0.0"},
+    {description, ""},
+    {modules, []},
+    {registered, []},
+    {applications, [kernel, stdlib]}
+]}.
+"#
+    );
+}
+
 #[test]
 fn module_test() {
     use std::collections::HashMap;
@@ -21,6 +69,7 @@ fn module_test() {
             accessors: HashMap::new(),
         },
         name: vec!["magic".to_string()],
+        documentation: vec![],
         statements: vec![
             Statement::ExternalType {
                 doc: None,
@@ -36,6 +85,7 @@ fn module_test() {
                 name: "Any".to_string(),
                 args: vec![],
                 constructors: vec![RecordConstructor {
+                    doc: None,
                     location: Default::default(),
                     name: "Ok".to_string(),
                     args: vec![],
@@ -124,6 +174,7 @@ map() ->
             accessors: HashMap::new(),
         },
         name: vec!["term".to_string()],
+        documentation: vec![],
         statements: vec![
             Statement::Fn {
                 doc: None,
@@ -269,6 +320,7 @@ map() ->
                         location: Default::default(),
                         name: "OneTwo".to_string(),
                     },
+                    annotation: None,
                     then: Box::new(TypedExpr::Var {
                         location: Default::default(),
                         constructor: ValueConstructor {
@@ -429,6 +481,7 @@ tup() ->
             accessors: HashMap::new(),
         },
         name: vec!["term".to_string()],
+        documentation: vec![],
         statements: vec![Statement::Fn {
             doc: None,
             return_type: typ::int(),
@@ -535,6 +588,7 @@ some_function(
             accessors: HashMap::new(),
         },
         name: vec!["vars".to_string()],
+        documentation: vec![],
         statements: vec![
             Statement::Fn {
                 doc: None,
@@ -650,6 +704,7 @@ moddy4() ->
             accessors: HashMap::new(),
         },
         name: vec!["my_mod".to_string()],
+        documentation: vec![],
         statements: vec![Statement::Fn {
             doc: None,
             return_type: typ::int(),
@@ -810,6 +865,7 @@ go() ->
             accessors: HashMap::new(),
         },
         name: vec!["funny".to_string()],
+        documentation: vec![],
         statements: vec![
             Statement::Fn {
                 doc: None,
@@ -1844,6 +1900,277 @@ main(Args) ->
 "#,
     );
 
+    assert_erl!(
+        r#"
+pub fn main(x) {
+  case x {
+    _ if x == "admin" -> 1
+    _ if x == "" -> 2
+    _ -> 0
+  }
+}
+"#,
+        r#"-module(the_app).
+-compile(no_auto_import).
+
+-export([main/1]).
+
+main(X) ->
+    case X of
+        _ when X =:= <<"admin"/utf8>> ->
+            1;
+
+        _ when X =:= <<""/utf8>> ->
+            2;
+
+        _ ->
+            0
+    end.
+"#,
+    );
+
+    assert_erl!(
+        r#"
+pub fn main(x) {
+  case x {
+    _ if x >. 0.5 -> 1
+    _ -> 0
+  }
+}
+"#,
+        r#"-module(the_app).
+-compile(no_auto_import).
+
+-export([main/1]).
+
+main(X) ->
+    case X of
+        _ when X > 0.5 ->
+            1;
+
+        _ ->
+            0
+    end.
+"#,
+    );
+
+    assert_erl!(
+        r#"
+pub fn main(x) {
+  case x {
+    _ if x == tuple(1, 2.0) -> 1
+    _ -> 0
+  }
+}
+"#,
+        r#"-module(the_app).
+-compile(no_auto_import).
+
+-export([main/1]).
+
+main(X) ->
+    case X of
+        _ when X =:= {1, 2.0} ->
+            1;
+
+        _ ->
+            0
+    end.
+"#,
+    );
+
+    assert_erl!(
+        r#"
+pub fn main(x) {
+  case x {
+    _ if x == Ok(Nil) -> 1
+    _ if x == Error(tuple(1, True)) -> 2
+    _ -> 0
+  }
+}
+"#,
+        r#"-module(the_app).
+-compile(no_auto_import).
+
+-export([main/1]).
+
+main(X) ->
+    case X of
+        _ when X =:= {ok, nil} ->
+            1;
+
+        _ when X =:= {error, {1, true}} ->
+            2;
+
+        _ ->
+            0
+    end.
+"#,
+    );
+
+    assert_erl!(
+        r#"
+pub fn main(pair: tuple(Int, Int)) {
+  case pair {
+    _ if pair.1 > 3 -> pair.0
+    _ -> 0
+  }
+}
+"#,
+        r#"-module(the_app).
+-compile(no_auto_import).
+
+-export([main/1]).
+
+main(Pair) ->
+    case Pair of
+        _ when erlang:element(2, Pair) > 3 ->
+            erlang:element(1, Pair);
+
+        _ ->
+            0
+    end.
+"#,
+    );
+
+    assert_erl!(
+        r#"
+pub type Person { Person(name: String, age: Int) }
+pub fn is_admin(person: Person) {
+  case person {
+    _ if person.name == "admin" -> True
+    _ -> False
+  }
+}
+"#,
+        r#"-module(the_app).
+-compile(no_auto_import).
+
+-export([is_admin/1]).
+
+is_admin(Person) ->
+    case Person of
+        _ when erlang:element(2, Person) =:= <<"admin"/utf8>> ->
+            true;
+
+        _ ->
+            false
+    end.
+"#,
+    );
+
+    // Numeric literals in other bases
+    assert_erl!(
+        r#"
+pub fn main(x) {
+  case x {
+    0xFF -> 1_000_000
+    -0o17 -> 0b1010
+    _ if x > 0xfF -> -0x1
+    _ -> 1
+  }
+}
+
+pub fn floats() {
+  tuple(1.5e-3, 1.e3, 1_000.0, 2.0E10)
+}
+"#,
+        r#"-module(the_app).
+-compile(no_auto_import).
+
+-export([main/1, floats/0]).
+
+main(X) ->
+    case X of
+        16#FF ->
+            1000000;
+
+        -8#17 ->
+            2#1010;
+
+        _ when X > 16#fF ->
+            -16#1;
+
+        _ ->
+            1
+    end.
+
+floats() ->
+    {1.5e-3, 1.0e3, 1000.0, 2.0E10}.
+"#,
+    );
+
+    // Unary operators
+    assert_erl!(
+        r#"
+pub fn main(a, b, c) {
+  tuple(-a, -.b, !c, -{ a + 1 }, !{ c && True }, - -1)
+}
+"#,
+        r#"-module(the_app).
+-compile(no_auto_import).
+
+-export([main/3]).
+
+main(A, B, C) ->
+    {-A, -B, not C, -(A + 1), not (C andalso true), -(-1)}.
+"#,
+    );
+
+    // String concatenation
+    assert_erl!(
+        r#"
+pub fn greet(name) {
+  "Hello, " <> name <> "!"
+}
+
+pub fn shout(name) {
+  greet(name) <> "!!"
+}
+"#,
+        r#"-module(the_app).
+-compile(no_auto_import).
+
+-export([greet/1, shout/1]).
+
+greet(Name) ->
+    <<"Hello, "/utf8, Name/binary, "!"/utf8>>.
+
+shout(Name) ->
+    <<(greet(Name))/binary, "!!"/utf8>>.
+"#,
+    );
+
+    // String prefix patterns
+    assert_erl!(
+        r#"
+pub fn main(x) {
+  case x {
+    "Hello, " <> name -> name
+    "Bye" <> _ -> ""
+    _ -> x
+  }
+}
+"#,
+        r#"-module(the_app).
+-compile(no_auto_import).
+
+-export([main/1]).
+
+main(X) ->
+    case X of
+        <<"Hello, "/utf8, Name/binary>> ->
+            Name;
+
+        <<"Bye"/utf8, _/binary>> ->
+            <<""/utf8>>;
+
+        _ ->
+            X
+    end.
+"#,
+    );
+
     assert_erl!(
         r#"
 pub fn main() {
@@ -103,10 +103,53 @@ pub enum Error {
         err: Option<String>,
     },
 
+    InvalidProjectConfig {
+        path: PathBuf,
+        src: Src,
+        location: crate::ast::SrcSpan,
+        message: String,
+    },
+
     StandardIO {
         action: StandardIOAction,
         err: Option<std::io::ErrorKind>,
     },
+
+    UnknownPackage {
+        name: Name,
+    },
+
+    PackageIndex {
+        package: Name,
+        err: String,
+    },
+
+    DependencyConflict {
+        package: Name,
+        required_by: Vec<(String, String)>,
+    },
+
+    DependencyCycle {
+        packages: Vec<Name>,
+    },
+
+    PathDependencyNameMismatch {
+        path: PathBuf,
+        expected: Name,
+        found: Name,
+    },
+
+    ChecksumMismatch {
+        package: Name,
+        version: String,
+        expected: String,
+        actual: String,
+    },
+
+    InvalidPackage {
+        package: Name,
+        reason: String,
+    },
 }
 
 #[derive(Debug, PartialEq)]
@@ -130,6 +173,8 @@ pub enum FileIOAction {
     Delete,
     Create,
     WriteTo,
+    Rename,
+    Watch,
     FindParent,
 }
 
@@ -142,6 +187,8 @@ impl FileIOAction {
             FileIOAction::Delete => "delete",
             FileIOAction::Create => "create",
             FileIOAction::WriteTo => "write to",
+            FileIOAction::Rename => "rename",
+            FileIOAction::Watch => "watch",
             FileIOAction::FindParent => "find the parent of",
         }
     }
@@ -209,7 +256,7 @@ impl Error {
                     "The application module `{}` is importing the test module `{}`.
 
 Test modules are not included in production builds so test modules
-cannot import them. Perhaps move the `{}` module to the src directory.",
+cannot import them. Perhaps move the `{}` module to a src directory.",
                     src_module, test_module, test_module,
                 )
                 .unwrap();
@@ -266,6 +313,23 @@ Second: {}
                 write_project(buffer, diagnostic);
             }
 
+            Error::InvalidProjectConfig {
+                path,
+                src,
+                location,
+                message,
+            } => {
+                let diagnostic = ErrorDiagnostic {
+                    title: "Invalid project config".to_string(),
+                    label: "".to_string(),
+                    file: path.to_str().unwrap().to_string(),
+                    src: src.to_string(),
+                    location: location.clone(),
+                };
+                write(buffer, diagnostic);
+                writeln!(buffer, "{}", message).unwrap();
+            }
+
             Error::Type { path, src, error } => match error {
                 UnknownLabel {
                     label,
@@ -959,6 +1023,39 @@ but this one uses {}. Rewrite this using the fn({}) {{ ... }} syntax.",
                                 )
                                 .expect("error pretty buffer write");
                             }
+
+                            Error::InvalidInt { location } => {
+                                let diagnostic = ErrorDiagnostic {
+                                    title: "Invalid integer".to_string(),
+                                    label: "".to_string(),
+                                    file: path.to_str().unwrap().to_string(),
+                                    src: src.to_string(),
+                                    location: location.clone(),
+                                };
+                                write(buffer, diagnostic);
+                                writeln!(
+                                    buffer,
+                                    "Hexadecimal, octal, and binary integers must have at least one digit
+after their prefix, e.g. 0xFF."
+                                )
+                                .expect("error pretty buffer write");
+                            }
+
+                            Error::IntTooLarge { location } => {
+                                let diagnostic = ErrorDiagnostic {
+                                    title: "Invalid integer".to_string(),
+                                    label: "".to_string(),
+                                    file: path.to_str().unwrap().to_string(),
+                                    src: src.to_string(),
+                                    location: location.clone(),
+                                };
+                                write(buffer, diagnostic);
+                                writeln!(
+                                    buffer,
+                                    "This integer is too large to be used as an index."
+                                )
+                                .expect("error pretty buffer write");
+                            }
                         }
                     }
                 }
@@ -966,6 +1063,30 @@ but this one uses {}. Rewrite this using the fn({}) {{ ... }} syntax.",
 
             Error::ImportCycle { modules } => import_cycle(buffer, modules.as_ref()),
 
+            Error::DependencyCycle { packages } => dependency_cycle(buffer, packages.as_ref()),
+
+            Error::PathDependencyNameMismatch {
+                path,
+                expected,
+                found,
+            } => {
+                let diagnostic = ProjectErrorDiagnostic {
+                    title: "Dependency name mismatch".to_string(),
+                    label: format!(
+                        "The package at {} is depended upon as `{}`,
+but its gleam.toml names it `{}`.
+
+Change the name in the [dependencies] section to `{}`.
+",
+                        path.to_str().unwrap(),
+                        expected,
+                        found,
+                        found
+                    ),
+                };
+                write_project(buffer, diagnostic);
+            }
+
             Error::UnknownImport {
                 module,
                 import,
@@ -1013,6 +1134,94 @@ but it cannot be found.",
                 };
                 write_project(buffer, diagnostic);
             }
+
+            Error::UnknownPackage { name } => {
+                let diagnostic = ProjectErrorDiagnostic {
+                    title: "Unknown package".to_string(),
+                    label: format!(
+                        "The package `{}` could not be found in the package index.
+Check the name in your gleam.toml for typos.
+",
+                        name
+                    ),
+                };
+                write_project(buffer, diagnostic);
+            }
+
+            Error::PackageIndex { package, err } => {
+                let diagnostic = ProjectErrorDiagnostic {
+                    title: "Package index failure".to_string(),
+                    label: format!(
+                        "An error occurred while trying to look up the package `{}`
+in the package index:
+
+    {}
+",
+                        package, err
+                    ),
+                };
+                write_project(buffer, diagnostic);
+            }
+
+            Error::DependencyConflict {
+                package,
+                required_by,
+            } => {
+                let requirements = required_by
+                    .iter()
+                    .map(|(by, requirement)| {
+                        format!("    {} requires {} {}", by, package, requirement)
+                    })
+                    .join("\n");
+                let diagnostic = ProjectErrorDiagnostic {
+                    title: "Dependency conflict".to_string(),
+                    label: format!(
+                        "There is no version of `{}` that satisfies all of these requirements:
+
+{}
+",
+                        package, requirements
+                    ),
+                };
+                write_project(buffer, diagnostic);
+            }
+
+            Error::ChecksumMismatch {
+                package,
+                version,
+                expected,
+                actual,
+            } => {
+                let diagnostic = ProjectErrorDiagnostic {
+                    title: "Checksum mismatch".to_string(),
+                    label: format!(
+                        "The checksum of {} {} does not match the one recorded in gleam.lock.
+
+Expected: {}
+Found:    {}
+
+The package may have been altered since it was locked. If this change is
+expected delete gleam.lock and _build/packages and then build again.
+",
+                        package, version, expected, actual
+                    ),
+                };
+                write_project(buffer, diagnostic);
+            }
+
+            Error::InvalidPackage { package, reason } => {
+                let diagnostic = ProjectErrorDiagnostic {
+                    title: "Invalid package".to_string(),
+                    label: format!(
+                        "The package `{}` cannot be published to Hex:
+
+    {}
+",
+                        package, reason
+                    ),
+                };
+                write_project(buffer, diagnostic);
+            }
         }
     }
 
@@ -1050,16 +1259,48 @@ fn std_io_error_kind_text(kind: &std::io::ErrorKind) -> String {
 
 fn import_cycle(buffer: &mut Buffer, modules: &[Vec<String>]) {
     use std::io::Write;
-    use termcolor::{Color, ColorSpec, WriteColor};
     write_title(buffer, "Import cycle");
     writeln!(
         buffer,
         "The import statements for these modules form a cycle:
+"
+    )
+    .unwrap();
+    let names: Vec<_> = modules.iter().map(|name| name.join("/")).collect();
+    write_cycle(buffer, names.as_slice());
+    writeln!(
+        buffer,
+        "
+Gleam doesn't support import cycles like these, please break the
+cycle to continue."
+    )
+    .unwrap();
+}
 
-    ┌─────┐"
+fn dependency_cycle(buffer: &mut Buffer, packages: &[String]) {
+    use std::io::Write;
+    write_title(buffer, "Dependency cycle");
+    writeln!(
+        buffer,
+        "The path dependencies of these packages form a cycle:
+"
     )
     .unwrap();
-    for (index, name) in modules.iter().enumerate() {
+    write_cycle(buffer, packages);
+    writeln!(
+        buffer,
+        "
+Packages cannot depend upon themselves, please break the cycle to
+continue."
+    )
+    .unwrap();
+}
+
+fn write_cycle(buffer: &mut Buffer, names: &[String]) {
+    use std::io::Write;
+    use termcolor::{Color, ColorSpec, WriteColor};
+    writeln!(buffer, "    ┌─────┐").unwrap();
+    for (index, name) in names.iter().enumerate() {
         if index != 0 {
             writeln!(buffer, "    │     ↓").unwrap();
         }
@@ -1067,17 +1308,10 @@ fn import_cycle(buffer: &mut Buffer, modules: &[Vec<String>]) {
         buffer
             .set_color(ColorSpec::new().set_fg(Some(Color::Cyan)))
             .unwrap();
-        writeln!(buffer, "{}", name.join("/")).unwrap();
+        writeln!(buffer, "{}", name).unwrap();
         buffer.set_color(&ColorSpec::new()).unwrap();
     }
-    writeln!(
-        buffer,
-        "    └─────┘
-
-Gleam doesn't support import cycles like these, please break the
-cycle to continue."
-    )
-    .unwrap();
+    writeln!(buffer, "    └─────┘").unwrap();
 }
 
 struct ErrorDiagnostic {
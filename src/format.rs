@@ -24,6 +24,7 @@ pub fn pretty(src: &str) -> Result<String, crate::parser::LalrpopError> {
 
 #[derive(Debug, Clone)]
 pub struct Formatter<'a> {
+    module_comments: &'a [Comment<'a>],
     comments: &'a [Comment<'a>],
     doc_comments: &'a [Comment<'a>],
 }
@@ -31,6 +32,7 @@ pub struct Formatter<'a> {
 impl<'a> Formatter<'a> {
     pub fn new() -> Self {
         Self {
+            module_comments: &[],
             comments: &[],
             doc_comments: &[],
         }
@@ -38,6 +40,7 @@ impl<'a> Formatter<'a> {
 
     pub fn with_comments(comments: &'a ModuleComments) -> Self {
         Self {
+            module_comments: comments.module_comments.as_slice(),
             comments: comments.comments.as_slice(),
             doc_comments: comments.doc_comments.as_slice(),
         }
@@ -91,6 +94,24 @@ impl<'a> Formatter<'a> {
             nil()
         };
 
+        // Module comments are kept at the top of the module, wherever they
+        // were written
+        let module_comments = if self.module_comments.is_empty() {
+            nil()
+        } else {
+            concat(
+                self.module_comments
+                    .iter()
+                    .map(|comment| "////".to_doc().append(comment.content))
+                    .intersperse(line()),
+            )
+            .append(if has_imports || has_declarations {
+                lines(2)
+            } else {
+                nil()
+            })
+        };
+
         let doc_comments = concat(
             self.doc_comments
                 .into_iter()
@@ -102,7 +123,8 @@ impl<'a> Formatter<'a> {
                 .map(|comment| line().append("//").append(comment.content)),
         );
 
-        imports
+        module_comments
+            .append(imports)
             .append(sep)
             .append(declarations)
             .append(doc_comments)
@@ -392,15 +414,34 @@ impl<'a> Formatter<'a> {
                 .append(name)
                 .append(self.expr(right.as_ref())),
 
+            UntypedExpr::UnaryOp { name, value, .. } => {
+                name.to_doc().append(match value.as_ref() {
+                    UntypedExpr::BinOp { .. } => self.expr(value).surround("{ ", " }"),
+
+                    UntypedExpr::Pipe { .. } => "{"
+                        .to_doc()
+                        .append(line().append(self.expr(value)).nest(INDENT))
+                        .append(line())
+                        .append("}"),
+
+                    _ => self.wrap_expr(value),
+                })
+            }
+
             UntypedExpr::Let {
                 value,
                 pattern,
+                annotation,
                 then,
                 assert,
                 ..
             } => force_break()
                 .append(if *assert { "assert " } else { "let " })
                 .append(self.pattern(pattern))
+                .append(match annotation {
+                    Some(a) => ": ".to_doc().append(self.type_ast(a)).group(),
+                    None => nil(),
+                })
                 .append(" = ")
                 .append(self.hanging_expr(value.as_ref()))
                 .append(line())
@@ -454,7 +495,7 @@ impl<'a> Formatter<'a> {
         }
     }
 
-    fn record_constructor(&mut self, constructor: &RecordConstructor) -> Document {
+    pub fn record_constructor(&mut self, constructor: &RecordConstructor) -> Document {
         let comments = self.pop_comments(constructor.location.start);
 
         let doc =
@@ -507,19 +548,28 @@ impl<'a> Formatter<'a> {
             .append("}")
     }
 
+    /// The signature of a function for its documentation. The inferred types
+    /// of the arguments are shown, rather than any annotations, so that every
+    /// argument has a type.
+    ///
     pub fn docs_fn_signature(
         &mut self,
         public: bool,
+        external: bool,
         name: &str,
-        args: &[TypedArg],
-        return_type: Arc<Type>,
+        args: Vec<(Option<Document>, Arc<Type>)>,
+        return_type: &Type,
+        printer: &mut typ::pretty::Printer<'_>,
     ) -> Document {
         pub_(public)
-            .append("fn ")
+            .append(if external { "external fn " } else { "fn " })
             .append(name.to_string())
-            .append(self.fn_args(args)) // TODO: Always show types
+            .append(wrap_args(args.into_iter().map(|(name, typ)| match name {
+                Some(name) => name.append(": ").append(printer.to_doc(&typ)).group(),
+                None => printer.to_doc(&typ).group(),
+            })))
             .append(" -> ".to_doc())
-            .append(typ::pretty::Printer::new().to_doc(return_type.as_ref()))
+            .append(printer.to_doc(return_type))
     }
 
     fn external_fn_arg(&mut self, arg: &ExternalFnArg) -> Document {
@@ -624,6 +674,12 @@ impl<'a> Formatter<'a> {
 
             Pattern::String { value, .. } => value.clone().to_doc().surround("\"", "\""),
 
+            Pattern::StringPrefix { prefix, rest, .. } => prefix
+                .clone()
+                .to_doc()
+                .surround("\"", "\" <> ")
+                .append(self.pattern(rest)),
+
             Pattern::Var { name, .. } => name.to_string().to_doc(),
 
             Pattern::Let { name, pattern, .. } => self
@@ -740,6 +796,17 @@ fn label(label: &Option<String>) -> Document {
     }
 }
 
+impl Documentable for &UnaryOp {
+    fn to_doc(self) -> Document {
+        match self {
+            UnaryOp::NegateInt => "-",
+            UnaryOp::NegateFloat => "-.",
+            UnaryOp::Not => "!",
+        }
+        .to_doc()
+    }
+}
+
 impl Documentable for &BinOp {
     fn to_doc(self) -> Document {
         match self {
@@ -764,6 +831,7 @@ impl Documentable for &BinOp {
             BinOp::DivInt => " / ",
             BinOp::DivFloat => " /. ",
             BinOp::ModuloInt => " % ",
+            BinOp::Concatenate => " <> ",
         }
         .to_doc()
     }
@@ -827,7 +895,39 @@ impl Documentable for &UntypedClauseGuard {
 
             ClauseGuard::Int { value, .. } => value.to_string().to_doc(),
 
+            ClauseGuard::Float { value, .. } => value.to_string().to_doc(),
+
+            ClauseGuard::String { value, .. } => value.to_string().to_doc().surround("\"", "\""),
+
             ClauseGuard::Var { name, .. } => name.to_string().to_doc(),
+
+            ClauseGuard::Tuple { elems, .. } => "tuple"
+                .to_doc()
+                .append(wrap_args(elems.iter().map(|e| e.to_doc()))),
+
+            ClauseGuard::Constructor {
+                module, name, args, ..
+            } => match module {
+                None => name.to_string().to_doc(),
+                Some(m) => m.to_string().to_doc().append(".").append(name.to_string()),
+            }
+            .append(if args.is_empty() {
+                nil()
+            } else {
+                wrap_args(args.iter().map(|a| label(&a.label).append(&a.value)))
+            }),
+
+            ClauseGuard::TupleIndex { tuple, index, .. } => {
+                tuple.as_ref().to_doc().append(".").append(*index)
+            }
+
+            ClauseGuard::FieldAccess {
+                container, label, ..
+            } => container
+                .as_ref()
+                .to_doc()
+                .append(".")
+                .append(label.to_string()),
         }
     }
 }
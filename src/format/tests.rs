@@ -689,6 +689,72 @@ pub external type Four
 "#
     );
 
+    assert_format!(
+        r#"fn main() {
+  "a" <> "b"
+}
+"#
+    );
+
+    assert_format!(
+        r#"fn main() {
+  "a" <> b <> "c"
+}
+"#
+    );
+
+    assert_format!(
+        r#"fn main() {
+  -x
+}
+"#
+    );
+
+    assert_format!(
+        r#"fn main() {
+  -.x
+}
+"#
+    );
+
+    assert_format!(
+        r#"fn main() {
+  !x
+}
+"#
+    );
+
+    assert_format!(
+        r#"fn main() {
+  !x && y
+}
+"#
+    );
+
+    assert_format!(
+        r#"fn main() {
+  -{ x + 1 }
+}
+"#
+    );
+
+    assert_format!(
+        r#"fn main() {
+  !{
+    x
+    |> f
+  }
+}
+"#
+    );
+
+    assert_format!(
+        r#"fn main() {
+  -x * 2
+}
+"#
+    );
+
     //
     // Int
     //
@@ -714,6 +780,34 @@ pub external type Four
 "#
     );
 
+    assert_format!(
+        r#"fn main() {
+  0xFF
+}
+"#
+    );
+
+    assert_format!(
+        r#"fn main() {
+  -0o17
+}
+"#
+    );
+
+    assert_format!(
+        r#"fn main() {
+  0b1010
+}
+"#
+    );
+
+    assert_format!(
+        r#"fn main() {
+  1_000_000
+}
+"#
+    );
+
     //
     // Float
     //
@@ -739,6 +833,23 @@ pub external type Four
 "#
     );
 
+    assert_format!(
+        r#"fn main() {
+  1.5e-3
+}
+"#
+    );
+
+    assert_format!(
+        r#"fn main() {
+  case x {
+    0xFF -> 1.0e10
+    _ if x > 0b1 -> 0.0
+  }
+}
+"#
+    );
+
     //
     // String
     //
@@ -1074,6 +1185,32 @@ World"
     // Let
     //
 
+    assert_format!(
+        r#"fn main() {
+  let x: Int = 1
+  Nil
+}
+"#
+    );
+
+    assert_format!(
+        r#"fn main() {
+  assert Ok(x): Result(Int, String) = y
+  Nil
+}
+"#
+    );
+
+    assert_format!(
+        r#"fn main() {
+  let xs: List(a) = [1, 2]
+  let x: Int = xs
+    |> list.length
+  x
+}
+"#
+    );
+
     assert_format!(
         r#"fn main() {
   let x = 1
@@ -1166,6 +1303,20 @@ World"
 "#
     );
 
+    //
+    // Pattern::StringPrefix
+    //
+
+    assert_format!(
+        r#"fn main() {
+  case x {
+    "hello " <> name -> name
+    "bye" <> _ -> ""
+  }
+}
+"#
+    );
+
     //
     // Pattern::Var
     //
@@ -1544,6 +1695,87 @@ World"
 "#
     );
 
+    assert_format!(
+        r#"fn main() {
+  case 1 {
+    _ if x == "admin" -> Nil
+  }
+}
+"#
+    );
+
+    assert_format!(
+        r#"fn main() {
+  case 1 {
+    _ if x >. 0.5 -> Nil
+  }
+}
+"#
+    );
+
+    assert_format!(
+        r#"fn main() {
+  case 1 {
+    _ if x == tuple(1, 2.0) -> Nil
+  }
+}
+"#
+    );
+
+    assert_format!(
+        r#"fn main() {
+  case 1 {
+    _ if x == Ok(Nil) -> Nil
+  }
+}
+"#
+    );
+
+    assert_format!(
+        r#"fn main() {
+  case 1 {
+    _ if x == Error(tuple(1, True)) -> Nil
+  }
+}
+"#
+    );
+
+    assert_format!(
+        r#"fn main() {
+  case 1 {
+    _ if x == one.Thing -> Nil
+  }
+}
+"#
+    );
+
+    assert_format!(
+        r#"fn main() {
+  case 1 {
+    _ if x == Person(name: "Al", age: 1) -> Nil
+  }
+}
+"#
+    );
+
+    assert_format!(
+        r#"fn main() {
+  case 1 {
+    _ if x.1 > 3 -> Nil
+  }
+}
+"#
+    );
+
+    assert_format!(
+        r#"fn main() {
+  case 1 {
+    _ if x.name == "admin" -> Nil
+  }
+}
+"#
+    );
+
     //
     // FieldAccess
     //
@@ -1720,6 +1952,57 @@ type Whatever {
 "#
     );
 
+    //
+    // Module comments
+    //
+
+    assert_format!("//// one\n");
+
+    assert_format!(
+        r#"//// one
+////two
+
+import one
+
+/// three
+type Whatever {
+  Whatever
+}
+"#
+    );
+
+    assert_format!(
+        r#"//// one
+
+fn main() {
+  Nil
+}
+"#
+    );
+
+    assert_eq!(
+        r#"//// one
+//// two
+
+import one
+
+fn main() {
+  Nil
+}
+"#,
+        pretty(
+            r#"import one
+//// one
+
+fn main() {
+  Nil
+}
+//// two
+"#
+        )
+        .unwrap()
+    );
+
     //
     // Comments
     //
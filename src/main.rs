@@ -1,15 +1,20 @@
 #![deny(warnings)]
 
 mod ast;
+mod config;
+mod deps;
 mod doc;
+mod doctest;
 mod erl;
 mod error;
 mod format;
 mod new;
+mod package;
 mod parser;
 mod pretty;
 mod project;
 mod typ;
+mod watch;
 
 lalrpop_mod!(
     #[allow(deprecated)]
@@ -32,12 +37,7 @@ extern crate lalrpop_util;
 #[macro_use]
 extern crate lazy_static;
 
-use crate::{
-    error::Error,
-    project::{ModuleOrigin, OutputFile, ProjectConfig},
-};
-use std::fs::File;
-use std::io::{Read, Write};
+use crate::{config::read_project_config, error::Error, project::ModuleOrigin};
 use std::path::PathBuf;
 use structopt::clap::AppSettings;
 use structopt::StructOpt;
@@ -54,8 +54,77 @@ enum Command {
         path: String,
         #[structopt(help = "generate docs for this package as well", long)]
         doc: bool,
+
+        #[structopt(help = "directory to write build output to", long = "out")]
+        out: Option<String>,
+
+        #[structopt(help = "rebuild whenever a source file changes", long)]
+        watch: bool,
+
+        #[structopt(help = "build the code examples in doc comments as tests", long)]
+        doctests: bool,
+
+        #[structopt(
+            long = "profile",
+            help = "the build profile to use",
+            possible_values = &config::Profile::VARIANTS,
+            case_insensitive = true,
+            default_value = "dev"
+        )]
+        profile: config::Profile,
+    },
+
+    #[structopt(name = "docs", about = "Generate documentation for a project")]
+    Docs {
+        #[structopt(help = "location of the project root", default_value = ".")]
+        path: String,
+
+        #[structopt(
+            long = "format",
+            help = "the format to generate documentation in",
+            possible_values = &doc::Format::VARIANTS,
+            case_insensitive = true,
+            default_value = "html"
+        )]
+        format: doc::Format,
+
+        #[structopt(help = "directory to write build output to", long = "out")]
+        out: Option<String>,
     },
 
+    #[structopt(name = "check", about = "Type check a project without building it")]
+    Check {
+        #[structopt(help = "location of the project root", default_value = ".")]
+        path: String,
+
+        #[structopt(
+            long = "profile",
+            help = "the build profile to use",
+            possible_values = &config::Profile::VARIANTS,
+            case_insensitive = true,
+            default_value = "dev"
+        )]
+        profile: config::Profile,
+
+        #[structopt(help = "check again whenever a source file changes", long)]
+        watch: bool,
+
+        #[structopt(help = "type check the code examples in doc comments", long)]
+        doctests: bool,
+    },
+
+    #[structopt(name = "clean", about = "Delete the build output of the project")]
+    Clean {
+        #[structopt(help = "location of the project root", default_value = ".")]
+        path: String,
+
+        #[structopt(help = "directory build output was written to", long = "out")]
+        out: Option<String>,
+    },
+
+    #[structopt(name = "deps", about = "Work with dependency packages")]
+    Deps(Dependencies),
+
     #[structopt(name = "new", about = "Create a new project")]
     New {
         #[structopt(help = "name of the project")]
@@ -76,6 +145,15 @@ enum Command {
         template: new::Template,
     },
 
+    #[structopt(name = "package", about = "Build a Hex package tarball")]
+    Package {
+        #[structopt(help = "location of the project root", default_value = ".")]
+        path: String,
+
+        #[structopt(help = "include the generated Erlang in the package", long)]
+        erlang: bool,
+    },
+
     #[structopt(name = "format", about = "Format source code")]
     Format {
         #[structopt(help = "files to format", conflicts_with = "stdin")]
@@ -96,9 +174,76 @@ enum Command {
     },
 }
 
+#[derive(StructOpt, Debug)]
+enum Dependencies {
+    #[structopt(
+        name = "update",
+        about = "Resolve dependencies again and update gleam.lock"
+    )]
+    Update {
+        #[structopt(help = "location of the project root", default_value = ".")]
+        path: String,
+    },
+}
+
 fn main() {
     let result = match Command::from_args() {
-        Command::Build { path, doc } => command_build(path, doc),
+        Command::Build {
+            path,
+            doc,
+            out,
+            profile,
+            watch: false,
+            doctests,
+        } => command_build(path, doc, out, profile, doctests, &mut Default::default()),
+
+        Command::Build {
+            path,
+            doc,
+            out,
+            profile,
+            watch: true,
+            doctests,
+        } => {
+            let mut cache = crate::project::Cache::default();
+            watch::run(&path, profile, || {
+                command_build(
+                    path.clone(),
+                    doc,
+                    out.clone(),
+                    profile,
+                    doctests,
+                    &mut cache,
+                )
+            })
+        }
+
+        Command::Check {
+            path,
+            profile,
+            watch: false,
+            doctests,
+        } => command_check(path, profile, doctests, &mut Default::default()),
+
+        Command::Check {
+            path,
+            profile,
+            watch: true,
+            doctests,
+        } => {
+            let mut cache = crate::project::Cache::default();
+            watch::run(&path, profile, || {
+                command_check(path.clone(), profile, doctests, &mut cache)
+            })
+        }
+
+        Command::Docs { path, format, out } => command_docs(path, format, out),
+
+        Command::Clean { path, out } => command_clean(path, out),
+
+        Command::Deps(Dependencies::Update { path }) => command_deps_update(path),
+
+        Command::Package { path, erlang } => command_package(path, erlang),
 
         Command::Format {
             stdin,
@@ -120,133 +265,370 @@ fn main() {
     }
 }
 
-fn command_build(root: String, write_docs: bool) -> Result<(), Error> {
-    let mut srcs = vec![];
-
-    // Read gleam.toml
-    let project_config = read_project_config(&root)?;
-
-    let root_path = PathBuf::from(&root);
-    let lib_dir = root_path.join("_build").join("default").join("lib");
-    let checkouts_dir = root_path.join("_checkouts");
-
-    for project_dir in [lib_dir, checkouts_dir]
-        .iter()
-        .filter_map(|d| std::fs::read_dir(d).ok())
-        .flat_map(|d| d.filter_map(Result::ok))
-        .map(|d| d.path())
-        .filter(|p| {
-            p.file_name().and_then(|os_string| os_string.to_str()) != Some(&project_config.name)
-        })
-    {
-        crate::project::collect_source(
-            project_dir.join("src"),
-            ModuleOrigin::Dependency,
-            &mut srcs,
-        )?;
-    }
-
-    // Collect source code from top level project
-    crate::project::collect_source(root_path.join("src"), ModuleOrigin::Src, &mut srcs)?;
-    crate::project::collect_source(root_path.join("test"), ModuleOrigin::Test, &mut srcs)?;
+/// A project that has had its dependencies fetched and has been type checked.
+///
+struct Compiled {
+    project_config: config::ProjectConfig,
+    root_path: PathBuf,
+    analysed: Vec<crate::project::Analysed>,
+}
 
-    let analysed = crate::project::analysed(srcs)?;
+fn command_build(
+    root: String,
+    write_docs: bool,
+    out: Option<String>,
+    profile: config::Profile,
+    doctests: bool,
+    cache: &mut crate::project::Cache,
+) -> Result<(), Error> {
+    let Compiled {
+        project_config,
+        root_path,
+        analysed,
+    } = compile(&root, profile, doctests, cache)?;
 
     // Generate outputs (Erlang code, html documentation, etc)
     let mut output_files = vec![];
-    if write_docs {
-        let dir = root_path.join("doc");
-        crate::doc::generate_html(
+    let mut copied_files = vec![];
+    let out_path = create_absolute_dir(output_root(&root_path, &project_config, out))?;
+    let dir = if write_docs {
+        let dir = out_path.join("doc");
+        generate_html_docs(
             &project_config,
             analysed.as_slice(),
+            &root_path,
+            &dir,
             &mut output_files,
+            &mut copied_files,
+        )?;
+        dir
+    } else {
+        let output_dir = project_config.output_dir(profile);
+        let dir = out_path.join(output_dir);
+        crate::project::generate_erlang(analysed.as_slice(), &dir, output_dir, &mut output_files);
+        crate::project::generate_app_file(
+            &project_config,
+            &root_path,
+            analysed.as_slice(),
             &dir,
+            &mut output_files,
         );
-        delete_dir(&dir)?;
-    } else {
-        let dir = root_path.join("gen");
-        crate::project::generate_erlang(analysed.as_slice(), &mut output_files);
-        delete_dir(&dir)?;
-    }
+        dir
+    };
+
+    crate::project::write_outputs(&dir, output_files.as_slice(), copied_files.as_slice())?;
+    println!("Done!");
+
+    Ok(())
+}
 
-    // Delete the gen directory before generating the newly compiled files
-    for file in output_files {
-        write_file(file)?;
+/// Generates the documentation of the project. Only the modules in the
+/// source directories are documented, so the project is compiled with the
+/// prod profile.
+///
+fn command_docs(root: String, format: doc::Format, out: Option<String>) -> Result<(), Error> {
+    let Compiled {
+        project_config,
+        root_path,
+        analysed,
+    } = compile(&root, config::Profile::Prod, false, &mut Default::default())?;
+    let out_path = create_absolute_dir(output_root(&root_path, &project_config, out))?;
+
+    match format {
+        doc::Format::Html => {
+            let dir = out_path.join("doc");
+            let mut output_files = vec![];
+            let mut copied_files = vec![];
+            generate_html_docs(
+                &project_config,
+                analysed.as_slice(),
+                &root_path,
+                &dir,
+                &mut output_files,
+                &mut copied_files,
+            )?;
+            crate::project::write_outputs(&dir, output_files.as_slice(), copied_files.as_slice())?;
+        }
+
+        doc::Format::Json => crate::project::write_output(&crate::project::OutputFile {
+            path: out_path.join("doc.json"),
+            text: doc::json::generate(&project_config, analysed.as_slice()),
+        })?,
     }
     println!("Done!");
 
     Ok(())
 }
 
-fn delete_dir(dir: &PathBuf) -> Result<(), Error> {
-    if dir.exists() {
-        std::fs::remove_dir_all(&dir).map_err(|e| Error::FileIO {
-            action: error::FileIOAction::Delete,
+fn generate_html_docs(
+    project_config: &config::ProjectConfig,
+    analysed: &[crate::project::Analysed],
+    root_path: &PathBuf,
+    dir: &PathBuf,
+    output_files: &mut Vec<crate::project::OutputFile>,
+    copied_files: &mut Vec<crate::project::CopiedFile>,
+) -> Result<(), Error> {
+    let readme = std::fs::read_to_string(root_path.join("README.md")).unwrap_or_default();
+    let pages = crate::doc::read_pages(root_path, project_config)?;
+    crate::doc::generate_html(
+        project_config,
+        analysed,
+        &readme,
+        &pages,
+        root_path,
+        output_files,
+        copied_files,
+        dir,
+    );
+    Ok(())
+}
+
+fn command_check(
+    root: String,
+    profile: config::Profile,
+    doctests: bool,
+    cache: &mut crate::project::Cache,
+) -> Result<(), Error> {
+    let _ = compile(&root, profile, doctests, cache)?;
+    println!("Done!");
+    Ok(())
+}
+
+/// Reads the project config, fetches dependencies, and type checks the
+/// project along with its dependencies, and optionally the code examples in
+/// its doc comments.
+///
+fn compile(
+    root: &str,
+    profile: config::Profile,
+    doctests: bool,
+    cache: &mut crate::project::Cache,
+) -> Result<Compiled, Error> {
+    let mut srcs = vec![];
+
+    // Read gleam.toml
+    let project_config = read_project_config(root)?;
+
+    // Output paths are compared with the files already on disk, so the root
+    // is made absolute in the same way as the source directories are
+    let root_path = PathBuf::from(root)
+        .canonicalize()
+        .map_err(|e| Error::FileIO {
+            action: error::FileIOAction::Open,
             kind: error::FileKind::Directory,
-            path: dir.clone(),
+            path: PathBuf::from(root),
             err: Some(e.to_string()),
         })?;
+    let lib_dir = root_path.join("_build").join("default").join("lib");
+    let checkouts_dir = root_path.join("_checkouts");
+
+    // Find the local projects depended upon by path, and resolve the versions
+    // of any Hex dependencies declared in gleam.toml. When there are no Hex
+    // dependencies fall back to compiling whatever rebar3 has fetched.
+    let path_packages = deps::path_packages(&root_path, &project_config)?;
+    let requirements = deps::requirements(&project_config, &path_packages);
+    let project_dirs: Vec<PathBuf> = if requirements.values().all(|r| r.is_empty()) {
+        [lib_dir, checkouts_dir]
+            .iter()
+            .filter_map(|d| std::fs::read_dir(d).ok())
+            .flat_map(|d| d.filter_map(Result::ok))
+            .map(|d| d.path())
+            .filter(|p| {
+                let name = p.file_name().and_then(|os_string| os_string.to_str());
+                name != Some(&project_config.name)
+                    && !path_packages
+                        .iter()
+                        .any(|package| Some(&*package.name) == name)
+            })
+            .collect()
+    } else {
+        let manifest = deps::download(
+            deps::default_index().as_ref(),
+            &root_path,
+            &requirements,
+            false,
+        )?;
+        manifest
+            .packages
+            .iter()
+            .map(|package| {
+                let checkout = checkouts_dir.join(&package.name);
+                if checkout.is_dir() {
+                    checkout
+                } else {
+                    deps::package_dir(&root_path, &package.name)
+                }
+            })
+            .collect()
+    };
+    let mut src_dirs: Vec<PathBuf> = project_dirs.iter().map(|dir| dir.join("src")).collect();
+    for package in path_packages {
+        src_dirs.extend(package.config.src_dirs.iter().map(|d| package.dir.join(d)));
     }
-    Ok(())
-}
 
-pub fn write_file(file: OutputFile) -> Result<(), Error> {
-    let OutputFile { path, text } = file;
+    for src_dir in src_dirs {
+        crate::project::collect_source(src_dir, ModuleOrigin::Dependency, &mut srcs)?;
+    }
 
-    let dir_path = path.parent().ok_or_else(|| Error::FileIO {
-        action: error::FileIOAction::FindParent,
-        kind: error::FileKind::Directory,
-        path: path.clone(),
-        err: None,
-    })?;
+    // Collect source code from top level project
+    for dir in project_config.src_dirs.iter() {
+        crate::project::collect_source(root_path.join(dir), ModuleOrigin::Src, &mut srcs)?;
+    }
+    if project_config.include_tests(profile) {
+        for dir in project_config.test_dirs.iter() {
+            crate::project::collect_source(root_path.join(dir), ModuleOrigin::Test, &mut srcs)?;
+        }
+    }
 
-    std::fs::create_dir_all(dir_path).map_err(|e| Error::FileIO {
+    // Generate test modules from the code examples in doc comments. Errors
+    // in them are reported against the doc comments they came from.
+    let documented = if doctests {
+        let inputs = crate::doctest::inputs(&srcs);
+        let documented = crate::doctest::documented(&srcs, &inputs);
+        srcs.extend(inputs);
+        documented
+    } else {
+        crate::doctest::Documented::new()
+    };
+
+    let analysed = cache
+        .analysed(srcs)
+        .map_err(|e| crate::doctest::locate_error(e, &documented))?;
+
+    Ok(Compiled {
+        project_config,
+        root_path,
+        analysed,
+    })
+}
+
+/// The directory build output is written to: the `--out` flag if given,
+/// otherwise the `out` directory of gleam.toml, otherwise the project root.
+///
+fn output_root(
+    root_path: &PathBuf,
+    project_config: &config::ProjectConfig,
+    out: Option<String>,
+) -> PathBuf {
+    match (out, &project_config.out) {
+        (Some(out), _) => PathBuf::from(out),
+        (None, Some(out)) => root_path.join(out),
+        (None, None) => root_path.clone(),
+    }
+}
+
+/// Creates the directory if it does not exist and returns its absolute path,
+/// so that paths within it can be compared with the files on disk.
+///
+fn create_absolute_dir(dir: PathBuf) -> Result<PathBuf, Error> {
+    std::fs::create_dir_all(&dir).map_err(|e| Error::FileIO {
         action: error::FileIOAction::Create,
         kind: error::FileKind::Directory,
-        path: dir_path.to_path_buf(),
+        path: dir.clone(),
         err: Some(e.to_string()),
     })?;
-
-    let mut f = File::create(&path).map_err(|e| Error::FileIO {
-        action: error::FileIOAction::Create,
-        kind: error::FileKind::File,
-        path: path.clone(),
+    dir.canonicalize().map_err(|e| Error::FileIO {
+        action: error::FileIOAction::Open,
+        kind: error::FileKind::Directory,
+        path: dir.clone(),
         err: Some(e.to_string()),
-    })?;
+    })
+}
 
-    f.write_all(text.as_bytes()).map_err(|e| Error::FileIO {
-        action: error::FileIOAction::WriteTo,
-        kind: error::FileKind::File,
-        path: path.clone(),
-        err: Some(e.to_string()),
-    })?;
+fn command_clean(root: String, out: Option<String>) -> Result<(), Error> {
+    let project_config = read_project_config(&root)?;
+    let root_path = PathBuf::from(&root);
+    let out_path = output_root(&root_path, &project_config, out);
+
+    // The files written by builds with each profile and by generating HTML
+    // docs. Only the files listed as written by the compiler are deleted.
+    let output_dirs = [
+        out_path.join(project_config.output_dir(config::Profile::Dev)),
+        out_path.join(project_config.output_dir(config::Profile::Prod)),
+        out_path.join("doc"),
+    ];
+    for dir in output_dirs.iter() {
+        crate::project::delete_outputs(dir)?;
+    }
+
+    // Downloaded package tarballs
+    crate::project::delete_dir(&root_path.join("_build").join("packages"))?;
+
+    let json_docs = out_path.join("doc.json");
+    if json_docs.is_file() {
+        std::fs::remove_file(&json_docs).map_err(|e| Error::FileIO {
+            action: error::FileIOAction::Delete,
+            kind: error::FileKind::File,
+            path: json_docs.clone(),
+            err: Some(e.to_string()),
+        })?;
+    }
+    println!("Done!");
     Ok(())
 }
 
-fn read_project_config(root: &str) -> Result<ProjectConfig, Error> {
-    let config_path = PathBuf::from(root).join("gleam.toml");
-
-    let mut file = File::open(&config_path).map_err(|e| Error::FileIO {
-        action: error::FileIOAction::Open,
-        kind: error::FileKind::File,
-        path: config_path.clone(),
-        err: Some(e.to_string()),
-    })?;
+/// Builds the project with the prod profile, to make sure that it compiles,
+/// and then writes its Hex package tarball to `_build`.
+///
+fn command_package(root: String, include_erlang: bool) -> Result<(), Error> {
+    let profile = config::Profile::Prod;
+    let Compiled {
+        project_config,
+        root_path,
+        analysed,
+    } = compile(&root, profile, false, &mut Default::default())?;
+    package::validate(&project_config)?;
+
+    let mut files = package::project_files(&root_path, &project_config)?;
+    if include_erlang {
+        let output_dir = project_config.output_dir(profile);
+        let dir = root_path.join(output_dir);
+        let mut outputs = vec![];
+        crate::project::generate_erlang(analysed.as_slice(), &dir, output_dir, &mut outputs);
+        let outputs = outputs
+            .into_iter()
+            .filter(|file| file.path.starts_with(&dir))
+            .collect();
+        files.extend(package::generated_files(&root_path, outputs));
+    }
 
-    let mut toml = String::new();
-    file.read_to_string(&mut toml).map_err(|e| Error::FileIO {
-        action: error::FileIOAction::Read,
+    let tarball = package::tarball(&project_config, files.as_slice())?;
+    let path = create_absolute_dir(root_path.join("_build"))?.join(format!(
+        "{}-{}.tar",
+        project_config.name, project_config.version
+    ));
+    std::fs::write(&path, &tarball).map_err(|e| Error::FileIO {
+        action: error::FileIOAction::WriteTo,
         kind: error::FileKind::File,
-        path: config_path.clone(),
+        path: path.clone(),
         err: Some(e.to_string()),
     })?;
 
-    let project_config = toml::from_str(&toml).map_err(|e| Error::FileIO {
-        action: error::FileIOAction::Parse,
-        kind: error::FileKind::File,
-        path: config_path.clone(),
-        err: Some(e.to_string()),
-    })?;
+    for file in files {
+        println!("  {}", file.path);
+    }
+    println!(
+        "Packaged {} {} into {}\nChecksum: {}",
+        project_config.name,
+        project_config.version,
+        path.to_string_lossy(),
+        deps::checksum(&tarball)
+    );
+    Ok(())
+}
 
-    Ok(project_config)
+fn command_deps_update(root: String) -> Result<(), Error> {
+    let project_config = read_project_config(&root)?;
+    let root_path = PathBuf::from(&root);
+    let path_packages = deps::path_packages(&root_path, &project_config)?;
+    let manifest = deps::download(
+        deps::default_index().as_ref(),
+        &root_path,
+        &deps::requirements(&project_config, &path_packages),
+        true,
+    )?;
+    for package in manifest.packages {
+        println!("Locked {} {}", package.name, package.version);
+    }
+    Ok(())
 }
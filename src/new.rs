@@ -1,3 +1,6 @@
+#[cfg(test)]
+mod tests;
+
 use crate::error::{Error, FileIOAction, FileKind, GleamExpect};
 use serde::{Deserialize, Serialize};
 use std::fs::File;
@@ -48,7 +51,10 @@ pub fn create(
     write(root_dir.join("LICENSE"), APACHE_2)?;
     write(root_dir.join(".gitignore"), GITIGNORE)?;
     write(root_dir.join("README.md"), &readme(&name, &description))?;
-    write(root_dir.join("gleam.toml"), &gleam_toml(&name))?;
+    write(
+        root_dir.join("gleam.toml"),
+        &gleam_toml(&name, &description, &template),
+    )?;
     write(test_dir.join(format!("{}_test.gleam", name)), &test(&name))?;
     write(src_dir.join(format!("{}.gleam", name)), &src(&name))?;
     write(workflows_dir.join("test.yml"), &github_ci(version))?;
@@ -118,8 +124,27 @@ fn write(path: PathBuf, contents: &str) -> Result<(), Error> {
     Ok(())
 }
 
-fn gleam_toml(name: &str) -> String {
-    format!("name = \"{}\"\n", name)
+fn gleam_toml(name: &str, description: &str, template: &Template) -> String {
+    let erlang = match template {
+        Template::Lib => "".to_string(),
+        Template::App => format!("\n[erlang]\napplication_start_module = \"{}_app\"\n", name),
+    };
+    format!(
+        r#"name = "{}"
+version = "1.0.0"
+description = "{}"
+licences = ["Apache-2.0"]
+
+# [repository]
+# type = "github"
+# user = "your-github-username"
+# repo = "{}"
+{}"#,
+        name,
+        description.replace('\\', "\\\\").replace('"', "\\\""),
+        name,
+        erlang
+    )
 }
 
 fn readme(name: &str, description: &str) -> String {
@@ -186,29 +211,28 @@ jobs:
     )
 }
 
+/// rebar3 only finds an application through its `.app.src` file, so a
+/// minimal one is written for it. `gleam build` generates the complete `.app`
+/// file from gleam.toml.
+///
 fn app_src(name: &str, description: &str, is_application: bool) -> String {
     let module = if is_application {
-        format!("\n  {{mod, {{{}_app, []}}}},", name)
+        format!("\n    {{mod, {{{}_app, []}}}},", name)
     } else {
         "".to_string()
     };
     format!(
-        r#"{{application, {},
- [{{description, "{}"}},
-  {{vsn, "1.0.0"}},
-  {{registered, []}},{}
-  {{applications,
-   [kernel,
-    stdlib
-   ]}},
-  {{env,[]}},
-  {{modules, []}},
-
-  {{licenses, ["Apache 2.0"]}},
-  {{links, []}}
+        r#"{{application, {}, [
+    {{description, "{}"}},
+    {{vsn, "1.0.0"}},
+    {{modules, []}},
+    {{registered, []}},{}
+    {{applications, [kernel, stdlib]}}
 ]}}.
 "#,
-        name, description, module,
+        name,
+        description.replace('\\', "\\\\").replace('"', "\\\""),
+        module,
     )
 }
 
@@ -478,6 +502,7 @@ _build
 ebin
 erl_crash.dump
 gen
+gen_prod
 log
 logs
 rebar3.crashdump
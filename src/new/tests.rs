@@ -0,0 +1,54 @@
+use super::*;
+
+#[test]
+fn create_test() {
+    let dir = std::env::temp_dir().join("gleam_new_create_test");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let create = |template: Template, name: &str| {
+        let root = dir.join(name);
+        create(
+            template,
+            name.to_string(),
+            None,
+            Some(root.to_string_lossy().to_string()),
+            "0.1.0",
+        )
+        .unwrap();
+        root
+    };
+
+    let root = create(Template::Lib, "my_lib");
+    assert!(root.join("gleam.toml").is_file());
+    assert!(root.join("rebar.config").is_file());
+    assert!(root.join("src/my_lib.gleam").is_file());
+    assert!(root.join("test/my_lib_test.gleam").is_file());
+
+    // rebar3 needs an .app.src file to find the application
+    let app_src = |root: &Path, name: &str| {
+        std::fs::read_to_string(root.join("src").join(format!("{}.app.src", name))).unwrap()
+    };
+    assert_eq!(
+        app_src(&root, "my_lib"),
+        r#"{application, my_lib, [
+    {description, "A Gleam program"},
+    {vsn, "1.0.0"},
+    {modules, []},
+    {registered, []},
+    {applications, [kernel, stdlib]}
+]}.
+"#
+    );
+
+    let root = create(Template::App, "my_app");
+    assert!(root.join("src/my_app_app.erl").is_file());
+    assert!(app_src(&root, "my_app").contains("{mod, {my_app_app, []}},"));
+    let config = crate::config::read_project_config(&root.to_string_lossy()).unwrap();
+    assert_eq!(
+        Some("my_app_app".to_string()),
+        config.erlang.application_start_module
+    );
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
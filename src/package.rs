@@ -0,0 +1,256 @@
+#[cfg(test)]
+mod tests;
+
+use crate::config::{Dependency, ProjectConfig};
+use crate::error::{Error, FileIOAction, FileKind};
+use crate::project::OutputFile;
+use itertools::Itertools;
+use std::path::{Path, PathBuf};
+
+/// The version of the Hex tarball format that packages are built in.
+const TARBALL_VERSION: &str = "3";
+
+/// The modification time given to each file in a tarball, so that packaging
+/// the same files twice results in the same checksum. This is the time used
+/// by Hex itself, 2000-01-01T00:00:00Z.
+const MTIME: u64 = 946_684_800;
+
+/// Files from the project root that are included in the package if they
+/// exist, in addition to the contents of the source directories.
+const ROOT_FILES: &[&str] = &[
+    "gleam.toml",
+    "rebar.config",
+    "README.md",
+    "CHANGELOG.md",
+    "LICENSE",
+    "LICENCE",
+];
+
+/// A file within a package, with its path relative to the project root.
+///
+#[derive(Debug, PartialEq)]
+pub struct PackageFile {
+    pub path: String,
+    pub contents: Vec<u8>,
+}
+
+/// Checks that the project has the metadata Hex requires of a package, and
+/// that it could be used by others once published.
+///
+pub fn validate(config: &ProjectConfig) -> Result<(), Error> {
+    let invalid = |reason: String| {
+        Err(Error::InvalidPackage {
+            package: config.name.clone(),
+            reason,
+        })
+    };
+
+    if config.description.is_empty() {
+        return invalid("It has no description. Add one to gleam.toml.".to_string());
+    }
+    if config.licences.is_empty() {
+        return invalid("It has no licences. Add them to gleam.toml.".to_string());
+    }
+    if let Some((name, _)) = config.path_dependencies().next() {
+        return invalid(format!(
+            "The dependency `{}` is a path dependency, which other projects
+cannot fetch. Depend upon a published version of it instead.",
+            name
+        ));
+    }
+    Ok(())
+}
+
+/// Reads the files of the project that are included in its package: the
+/// config, readme, and licence files, and everything in the source
+/// directories.
+///
+pub fn project_files(root: &Path, config: &ProjectConfig) -> Result<Vec<PackageFile>, Error> {
+    let mut files = vec![];
+    for name in ROOT_FILES {
+        let path = root.join(name);
+        if path.is_file() {
+            files.push(read(root, &path)?);
+        }
+    }
+
+    for dir in config.src_dirs.iter() {
+        for entry in walkdir::WalkDir::new(root.join(dir))
+            .sort_by(|a, b| a.file_name().cmp(b.file_name()))
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|e| e.file_type().is_file())
+        {
+            files.push(read(root, entry.path())?);
+        }
+    }
+    Ok(files)
+}
+
+/// Converts generated files within the project root into package files, so
+/// that the package can be used by build tools that cannot compile Gleam.
+///
+pub fn generated_files(root: &Path, outputs: Vec<OutputFile>) -> Vec<PackageFile> {
+    outputs
+        .into_iter()
+        .filter_map(|file| {
+            Some(PackageFile {
+                path: relative_path(root, &file.path)?,
+                contents: file.text.into_bytes(),
+            })
+        })
+        .collect()
+}
+
+/// Builds a tarball in the format expected by Hex. It contains the format
+/// version, the package metadata, a gzipped archive of the package files,
+/// and a checksum of the other three.
+///
+pub fn tarball(config: &ProjectConfig, files: &[PackageFile]) -> Result<Vec<u8>, Error> {
+    let metadata = metadata(config, files);
+    let contents = contents(files)?;
+    let mut checked = TARBALL_VERSION.as_bytes().to_vec();
+    checked.extend_from_slice(metadata.as_bytes());
+    checked.extend_from_slice(&contents);
+    let checksum = crate::deps::checksum(&checked);
+
+    archive(&[
+        ("VERSION", TARBALL_VERSION.as_bytes()),
+        ("metadata.config", metadata.as_bytes()),
+        ("contents.tar.gz", &contents),
+        ("CHECKSUM", checksum.as_bytes()),
+    ])
+}
+
+/// The metadata of the package, as a series of Erlang terms.
+///
+pub fn metadata(config: &ProjectConfig, files: &[PackageFile]) -> String {
+    let mut links: Vec<(String, String)> = vec![];
+    if let Some(url) = config.repository.url() {
+        links.push(("Repository".to_string(), url));
+    }
+    links.extend(
+        config
+            .links
+            .iter()
+            .map(|link| (link.title.clone(), link.href.clone())),
+    );
+
+    let requirements = config
+        .dependencies
+        .iter()
+        .filter_map(|(name, dependency)| match dependency {
+            Dependency::Hex(requirement) => Some(format!(
+                "{{{}, [{{{}, {}}}, {{{}, false}}, {{{}, {}}}]}}",
+                binary(name),
+                binary("app"),
+                binary(name),
+                binary("optional"),
+                binary("requirement"),
+                binary(requirement)
+            )),
+            Dependency::Path { .. } => None,
+        })
+        .join(", ");
+
+    let build_tool = if files.iter().any(|file| file.path == "rebar.config") {
+        "rebar3"
+    } else {
+        "gleam"
+    };
+
+    let terms = vec![
+        ("name", binary(&config.name)),
+        ("app", binary(&config.name)),
+        ("version", binary(&config.version)),
+        ("description", binary(&config.description)),
+        ("licenses", list(config.licences.iter().map(|l| binary(l)))),
+        (
+            "links",
+            list(
+                links
+                    .iter()
+                    .map(|(title, url)| format!("{{{}, {}}}", binary(title), binary(url))),
+            ),
+        ),
+        ("requirements", format!("[{}]", requirements)),
+        ("files", list(files.iter().map(|file| binary(&file.path)))),
+        ("build_tools", list(vec![binary(build_tool)].into_iter())),
+    ];
+
+    terms
+        .into_iter()
+        .map(|(key, value)| format!("{{{}, {}}}.\n", binary(key), value))
+        .collect()
+}
+
+fn binary(value: &str) -> String {
+    format!(
+        "<<\"{}\"/utf8>>",
+        value.replace('\\', "\\\\").replace('"', "\\\"")
+    )
+}
+
+fn list(mut values: impl Iterator<Item = String>) -> String {
+    format!("[{}]", values.join(", "))
+}
+
+fn contents(files: &[PackageFile]) -> Result<Vec<u8>, Error> {
+    let entries: Vec<(&str, &[u8])> = files
+        .iter()
+        .map(|file| (file.path.as_str(), file.contents.as_slice()))
+        .collect();
+    let tar = archive(&entries)?;
+
+    let mut encoder = flate2::write::GzEncoder::new(vec![], flate2::Compression::default());
+    std::io::Write::write_all(&mut encoder, &tar).map_err(archive_error)?;
+    encoder.finish().map_err(archive_error)
+}
+
+fn archive(entries: &[(&str, &[u8])]) -> Result<Vec<u8>, Error> {
+    let mut builder = tar::Builder::new(vec![]);
+    for (path, contents) in entries {
+        let mut header = tar::Header::new_ustar();
+        header.set_path(path).map_err(archive_error)?;
+        header.set_size(contents.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(MTIME);
+        header.set_cksum();
+        builder.append(&header, *contents).map_err(archive_error)?;
+    }
+    builder.into_inner().map_err(archive_error)
+}
+
+fn archive_error(err: std::io::Error) -> Error {
+    Error::FileIO {
+        action: FileIOAction::Create,
+        kind: FileKind::File,
+        path: PathBuf::from("package tarball"),
+        err: Some(err.to_string()),
+    }
+}
+
+fn read(root: &Path, path: &Path) -> Result<PackageFile, Error> {
+    let contents = std::fs::read(path).map_err(|e| Error::FileIO {
+        action: FileIOAction::Read,
+        kind: FileKind::File,
+        path: path.to_path_buf(),
+        err: Some(e.to_string()),
+    })?;
+    Ok(PackageFile {
+        path: relative_path(root, path).unwrap_or_else(|| path.to_string_lossy().to_string()),
+        contents,
+    })
+}
+
+/// The path relative to the root, with `/` separators as used in tarballs.
+///
+fn relative_path(root: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(root).ok()?;
+    Some(
+        relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .join("/"),
+    )
+}
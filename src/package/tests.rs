@@ -0,0 +1,235 @@
+use super::*;
+use std::io::Read;
+
+fn config(toml: &str) -> ProjectConfig {
+    crate::config::parse(toml, &PathBuf::from("/gleam.toml")).unwrap()
+}
+
+fn file(path: &str, contents: &str) -> PackageFile {
+    PackageFile {
+        path: path.to_string(),
+        contents: contents.as_bytes().to_vec(),
+    }
+}
+
+/// The path and contents of each entry of a tarball.
+///
+fn entries(tarball: impl Read) -> Vec<(String, Vec<u8>)> {
+    tar::Archive::new(tarball)
+        .entries()
+        .unwrap()
+        .map(|entry| {
+            let mut entry = entry.unwrap();
+            let path = entry.path().unwrap().to_string_lossy().to_string();
+            let mut contents = vec![];
+            entry.read_to_end(&mut contents).unwrap();
+            (path, contents)
+        })
+        .collect()
+}
+
+#[test]
+fn validate_test() {
+    let package = |toml: &str| validate(&config(toml));
+    let invalid = |reason: &str| {
+        Err(Error::InvalidPackage {
+            package: "my_lib".to_string(),
+            reason: reason.to_string(),
+        })
+    };
+
+    assert_eq!(
+        Ok(()),
+        package(
+            r#"
+name = "my_lib"
+description = "A library"
+licences = ["Apache-2.0"]
+
+[dependencies]
+gleam_stdlib = "~> 0.8"
+"#
+        )
+    );
+
+    assert_eq!(
+        invalid("It has no description. Add one to gleam.toml."),
+        package(
+            r#"
+name = "my_lib"
+licences = ["Apache-2.0"]
+"#
+        )
+    );
+
+    assert_eq!(
+        invalid("It has no licences. Add them to gleam.toml."),
+        package(
+            r#"
+name = "my_lib"
+description = "A library"
+"#
+        )
+    );
+
+    assert_eq!(
+        invalid(
+            "The dependency `other` is a path dependency, which other projects
+cannot fetch. Depend upon a published version of it instead."
+        ),
+        package(
+            r#"
+name = "my_lib"
+description = "A library"
+licences = ["Apache-2.0"]
+
+[dependencies]
+other = { path = "../other" }
+"#
+        )
+    );
+}
+
+#[test]
+fn metadata_test() {
+    let config = config(
+        r#"
+name = "my_lib"
+version = "1.2.0"
+description = "A \"great\" library"
+licences = ["Apache-2.0", "MIT"]
+
+[repository]
+type = "github"
+user = "me"
+repo = "my_lib"
+
+[[links]]
+title = "Website"
+href = "https://example.com"
+
+[dependencies]
+gleam_stdlib = "~> 0.8"
+"#,
+    );
+    let files = vec![
+        file("gleam.toml", ""),
+        file("rebar.config", ""),
+        file("src/my_lib.gleam", ""),
+    ];
+
+    assert_eq!(
+        metadata(&config, &files),
+        r#"{<<"name"/utf8>>, <<"my_lib"/utf8>>}.
+{<<"app"/utf8>>, <<"my_lib"/utf8>>}.
+{<<"version"/utf8>>, <<"1.2.0"/utf8>>}.
+{<<"description"/utf8>>, <<"A \"great\" library"/utf8>>}.
+{<<"licenses"/utf8>>, [<<"Apache-2.0"/utf8>>, <<"MIT"/utf8>>]}.
+{<<"links"/utf8>>, [{<<"Repository"/utf8>>, <<"https://github.com/me/my_lib"/utf8>>}, {<<"Website"/utf8>>, <<"https://example.com"/utf8>>}]}.
+{<<"requirements"/utf8>>, [{<<"gleam_stdlib"/utf8>>, [{<<"app"/utf8>>, <<"gleam_stdlib"/utf8>>}, {<<"optional"/utf8>>, false}, {<<"requirement"/utf8>>, <<"~> 0.8"/utf8>>}]}]}.
+{<<"files"/utf8>>, [<<"gleam.toml"/utf8>>, <<"rebar.config"/utf8>>, <<"src/my_lib.gleam"/utf8>>]}.
+{<<"build_tools"/utf8>>, [<<"rebar3"/utf8>>]}.
+"#
+    );
+
+    let files = vec![file("gleam.toml", "")];
+    assert!(
+        metadata(&config, &files).ends_with("{<<\"build_tools\"/utf8>>, [<<\"gleam\"/utf8>>]}.\n")
+    );
+}
+
+#[test]
+fn tarball_test() {
+    let config = config(
+        r#"
+name = "my_lib"
+description = "A library"
+licences = ["Apache-2.0"]
+"#,
+    );
+    let files = vec![
+        file("gleam.toml", "name = \"my_lib\"\n"),
+        file("src/my_lib.gleam", "pub fn main() { 1 }\n"),
+        file("gen/src/my_lib.erl", "-module(my_lib).\n"),
+    ];
+    let tarball = tarball(&config, &files).unwrap();
+
+    // Packaging the same files again gives the same tarball
+    assert_eq!(tarball, super::tarball(&config, &files).unwrap());
+
+    let outer = entries(tarball.as_slice());
+    let names: Vec<&str> = outer.iter().map(|(path, _)| path.as_str()).collect();
+    assert_eq!(
+        names,
+        vec!["VERSION", "metadata.config", "contents.tar.gz", "CHECKSUM"]
+    );
+    assert_eq!(outer[0].1, b"3".to_vec());
+    assert_eq!(
+        String::from_utf8(outer[1].1.clone()).unwrap(),
+        metadata(&config, &files)
+    );
+
+    // The checksum covers the other files of the tarball
+    let mut checked = outer[0].1.clone();
+    checked.extend_from_slice(&outer[1].1);
+    checked.extend_from_slice(&outer[2].1);
+    assert_eq!(
+        String::from_utf8(outer[3].1.clone()).unwrap(),
+        crate::deps::checksum(&checked)
+    );
+
+    let contents = entries(flate2::read::GzDecoder::new(outer[2].1.as_slice()));
+    assert_eq!(
+        contents,
+        files
+            .iter()
+            .map(|file| (file.path.clone(), file.contents.clone()))
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn project_files_test() {
+    let dir = std::env::temp_dir().join("gleam_package_project_files_test");
+    let _ = std::fs::remove_dir_all(&dir);
+    let write = |path: &str| {
+        let path = dir.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, "").unwrap();
+    };
+    write("gleam.toml");
+    write("README.md");
+    write("notes.txt");
+    write("src/my_lib.gleam");
+    write("src/my_lib/b.gleam");
+    write("src/my_lib/a.gleam");
+    write("src/my_lib_ffi.erl");
+    write("lib/extra.gleam");
+    write("test/my_lib_test.gleam");
+
+    let config = config(
+        r#"
+name = "my_lib"
+src_dirs = ["src", "lib"]
+"#,
+    );
+    let paths: Vec<String> = project_files(&dir, &config)
+        .unwrap()
+        .into_iter()
+        .map(|file| file.path)
+        .collect();
+    assert_eq!(
+        paths,
+        vec![
+            "gleam.toml",
+            "README.md",
+            "src/my_lib/a.gleam",
+            "src/my_lib/b.gleam",
+            "src/my_lib.gleam",
+            "src/my_lib_ffi.erl",
+            "lib/extra.gleam",
+        ]
+    );
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -6,12 +6,19 @@ pub enum Error {
         location: crate::ast::SrcSpan,
         count: usize,
     },
+    InvalidInt {
+        location: crate::ast::SrcSpan,
+    },
+    IntTooLarge {
+        location: crate::ast::SrcSpan,
+    },
 }
 
 pub type LalrpopError = lalrpop_util::ParseError<usize, (usize, String), Error>;
 
 #[derive(Debug, PartialEq)]
 pub struct ModuleComments<'a> {
+    pub module_comments: Vec<Comment<'a>>,
     pub doc_comments: Vec<Comment<'a>>,
     pub comments: Vec<Comment<'a>>,
 }
@@ -45,6 +52,7 @@ pub fn strip_extra(src: &str) -> (String, ModuleComments<'_>) {
     enum Kind {
         Regular,
         Doc,
+        Module,
     }
     enum Mode {
         Normal,
@@ -56,6 +64,7 @@ pub fn strip_extra(src: &str) -> (String, ModuleComments<'_>) {
     let mut mode = Mode::Normal;
     let mut chars = UnicodeSegmentation::grapheme_indices(src, true).peekable();
     let mut comments = ModuleComments {
+        module_comments: vec![],
         doc_comments: vec![],
         comments: vec![],
     };
@@ -79,6 +88,11 @@ pub fn strip_extra(src: &str) -> (String, ModuleComments<'_>) {
                                 mode = Mode::Comment(Kind::Doc, outer_char_no);
                                 buffer.push(' ');
                                 chars.next();
+                                if let Some((_, "/")) = chars.peek() {
+                                    mode = Mode::Comment(Kind::Module, outer_char_no);
+                                    buffer.push(' ');
+                                    chars.next();
+                                }
                             }
                             Some((_, _c2)) => {
                                 mode = Mode::Comment(Kind::Regular, outer_char_no);
@@ -119,6 +133,7 @@ pub fn strip_extra(src: &str) -> (String, ModuleComments<'_>) {
                 "\n" => {
                     mode = Mode::Normal;
                     let content_start = match &kind {
+                        Kind::Module => start + 4,
                         Kind::Doc => start + 3,
                         Kind::Regular => start + 2,
                     };
@@ -127,6 +142,7 @@ pub fn strip_extra(src: &str) -> (String, ModuleComments<'_>) {
                         content: &src[content_start..outer_char_no],
                     };
                     match &kind {
+                        Kind::Module => &mut comments.module_comments,
                         Kind::Doc => &mut comments.doc_comments,
                         Kind::Regular => &mut comments.comments,
                     }
@@ -140,6 +156,7 @@ pub fn strip_extra(src: &str) -> (String, ModuleComments<'_>) {
 
     if let Mode::Comment(kind, start) = mode {
         let content_start = match &kind {
+            Kind::Module => start + 4,
             Kind::Doc => start + 3,
             Kind::Regular => start + 2,
         };
@@ -148,6 +165,7 @@ pub fn strip_extra(src: &str) -> (String, ModuleComments<'_>) {
             content: &src[content_start..],
         };
         match &kind {
+            Kind::Module => &mut comments.module_comments,
             Kind::Doc => &mut comments.doc_comments,
             Kind::Regular => &mut comments.comments,
         }
@@ -208,6 +226,7 @@ pub external fn a() -> Nil =
         "// 👨‍👩‍👧‍👧 unicode\n",
         "            \n",
         ModuleComments {
+            module_comments: vec![],
             doc_comments: vec![],
             comments: vec![Comment {
                 start: 0,
@@ -220,6 +239,7 @@ pub external fn a() -> Nil =
         "// hello\n",
         "        \n",
         ModuleComments {
+            module_comments: vec![],
             doc_comments: vec![],
             comments: vec![Comment {
                 start: 0,
@@ -232,6 +252,7 @@ pub external fn a() -> Nil =
         "// hello",
         "        ",
         ModuleComments {
+            module_comments: vec![],
             doc_comments: vec![],
             comments: vec![Comment {
                 start: 0,
@@ -246,6 +267,7 @@ pub external fn a() -> Nil =
         "/// hello\n",
         "         \n",
         ModuleComments {
+            module_comments: vec![],
             doc_comments: vec![Comment {
                 start: 0,
                 content: " hello",
@@ -258,6 +280,7 @@ pub external fn a() -> Nil =
         "/// hello",
         "         ",
         ModuleComments {
+            module_comments: vec![],
             doc_comments: vec![Comment {
                 start: 0,
                 content: " hello",
@@ -278,6 +301,7 @@ fn main() {
 }
 ",
         ModuleComments {
+            module_comments: vec![],
             doc_comments: vec![
                 Comment {
                     start: 0,
@@ -291,6 +315,94 @@ fn main() {
             comments: vec![],
         }
     );
+
+    // Testing module comment collection
+
+    assert_stripped!(
+        "//// A module\n\n/// hello\n",
+        "             \n\n         \n",
+        ModuleComments {
+            module_comments: vec![Comment {
+                start: 0,
+                content: " A module",
+            }],
+            doc_comments: vec![Comment {
+                start: 15,
+                content: " hello",
+            }],
+            comments: vec![],
+        }
+    );
+
+    assert_stripped!(
+        "////",
+        "    ",
+        ModuleComments {
+            module_comments: vec![Comment {
+                start: 0,
+                content: "",
+            }],
+            doc_comments: vec![],
+            comments: vec![],
+        }
+    );
+}
+
+#[test]
+fn parse_positive_int_test() {
+    assert_eq!(Some(0), parse_positive_int("0"));
+    assert_eq!(Some(1_000_000), parse_positive_int("1_000_000"));
+    assert_eq!(Some(255), parse_positive_int("0xFF"));
+    assert_eq!(Some(255), parse_positive_int("0xf_f"));
+    assert_eq!(Some(255), parse_positive_int("0x_FF"));
+    assert_eq!(Some(15), parse_positive_int("0o17"));
+    assert_eq!(Some(10), parse_positive_int("0b1010"));
+    assert_eq!(None, parse_positive_int("0x1_0000_0000_0000_0000"));
+}
+
+#[test]
+fn int_literal_test() {
+    let parse = |src: &'static str| crate::grammar::ModuleParser::new().parse(src);
+
+    assert!(parse("pub fn a() { 0x_F }").is_ok());
+    assert!(parse("pub fn a() { 0o_7 }").is_ok());
+    assert!(parse("pub fn a() { 0b_1 }").is_ok());
+    assert!(parse("pub fn a() { -1_0 }").is_ok());
+    assert!(parse("pub fn a() { tuple(1, 2).0b1 }").is_ok());
+
+    // A prefix must be followed by digits
+    let invalid = |src: &'static str, start, end| match parse(src) {
+        Err(lalrpop_util::ParseError::User {
+            error: Error::InvalidInt { location },
+        }) => assert_eq!(location, crate::ast::SrcSpan { start, end }),
+        other => panic!("expected an invalid int error, got {:?}", other),
+    };
+    invalid("pub fn a() { 0x_ }", 13, 16);
+    invalid("pub fn a() { 0o }", 13, 15);
+    invalid("pub fn a() { 0b__ }", 13, 17);
+    invalid("pub fn a() { -0x_ }", 13, 17);
+    invalid("pub fn a() { tuple(1, 2).0x_ }", 25, 28);
+    invalid("pub fn a(x) { case x { 0x_ -> 1 } }", 23, 26);
+
+    // A separator must be followed by digits
+    assert!(parse("pub fn a() { 1_ }").is_err());
+    assert!(parse("pub fn a() { 0xF_ }").is_err());
+    assert!(parse("pub fn a() { 1_.0 }").is_err());
+    assert!(parse("pub fn a() { 1._5 }").is_err());
+    assert!(parse("pub fn a() { 1.0_ }").is_err());
+    assert!(parse("pub fn a() { -1_.0 }").is_err());
+    assert!(parse("pub fn a() { 1_0.0_5 }").is_ok());
+    assert!(parse("pub fn a() { 1. }").is_ok());
+    assert!(parse("pub fn a() { 1.5e10 }").is_ok());
+
+    // Tuple indexes that do not fit in 64 bits are an error rather than a
+    // crash
+    match parse("pub fn a() { tuple(1, 2).0x1_0000_0000_0000_0000 }") {
+        Err(lalrpop_util::ParseError::User {
+            error: Error::IntTooLarge { location },
+        }) => assert_eq!(location, crate::ast::SrcSpan { start: 25, end: 48 }),
+        other => panic!("expected an int too large error, got {:?}", other),
+    }
 }
 
 pub fn seq(mut exprs: Vec<crate::ast::UntypedExpr>) -> crate::ast::UntypedExpr {
@@ -309,3 +421,18 @@ pub fn seq(mut exprs: Vec<crate::ast::UntypedExpr>) -> crate::ast::UntypedExpr {
 pub fn location(start: usize, end: usize) -> crate::ast::SrcSpan {
     crate::ast::SrcSpan { start, end }
 }
+
+/// Parses a positive integer literal, which may be written in hexadecimal
+/// (`0xFF`), octal (`0o17`), or binary (`0b1010`), and may contain `_`
+/// separators. Returns nothing if it is too large to fit in 64 bits.
+///
+pub fn parse_positive_int(literal: &str) -> Option<u64> {
+    let digits: String = literal.chars().filter(|c| *c != '_').collect();
+    let (radix, digits) = match digits.get(0..2) {
+        Some("0x") => (16, &digits[2..]),
+        Some("0o") => (8, &digits[2..]),
+        Some("0b") => (2, &digits[2..]),
+        _ => (10, digits.as_str()),
+    };
+    u64::from_str_radix(digits, radix).ok()
+}
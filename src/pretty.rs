@@ -99,6 +99,9 @@ pub enum Document {
 
     /// A string to render
     Text(String),
+
+    /// A string to render that takes up no width, such as markup
+    ZeroWidthText(String),
 }
 
 #[derive(Debug, Clone)]
@@ -133,6 +136,8 @@ fn fits(mut limit: isize, mut docs: Vector<(isize, Mode, Document)>) -> bool {
 
             Document::Text(s) => limit -= s.len() as isize,
 
+            Document::ZeroWidthText(_) => (),
+
             Document::Break { unbroken, .. } => match mode {
                 Mode::Broken => return true,
                 Mode::Unbroken => limit -= unbroken.len() as isize,
@@ -190,6 +195,8 @@ fn fmt(b: &mut String, limit: isize, mut width: isize, mut docs: Vector<(isize,
                 b.push_str(s.as_str());
             }
 
+            Document::ZeroWidthText(s) => b.push_str(s.as_str()),
+
             Document::Cons(left, right) => {
                 docs.push_front((indent, mode.clone(), *right));
                 docs.push_front((indent, mode, *left));
@@ -412,6 +419,18 @@ fn format_test() {
         }),
     );
     assert_eq!("broken\n".to_string(), format(100, doc));
+
+    let doc = Cons(
+        Box::new(ZeroWidthText("<b>".to_string())),
+        Box::new(Cons(
+            Box::new(Text("12345".to_string())),
+            Box::new(Break {
+                broken: "broken".to_string(),
+                unbroken: "unbroken".to_string(),
+            }),
+        )),
+    );
+    assert_eq!("<b>12345unbroken".to_string(), format(13, doc));
 }
 
 pub fn nil() -> Document {
@@ -426,6 +445,10 @@ pub fn lines(i: usize) -> Document {
     Document::Line(i)
 }
 
+pub fn zero_width_text(text: String) -> Document {
+    Document::ZeroWidthText(text)
+}
+
 pub fn force_break() -> Document {
     Document::ForceBreak
 }
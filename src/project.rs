@@ -4,18 +4,13 @@ mod tests;
 
 use crate::{
     ast::TypedModule,
+    config::ProjectConfig,
     error::{Error, FileIOAction, FileKind, GleamExpect},
     typ,
 };
-use serde::Deserialize;
 use source_tree::SourceTree;
-use std::collections::HashMap;
-use std::path::PathBuf;
-
-#[derive(Deserialize)]
-pub struct ProjectConfig {
-    pub name: String,
-}
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, PartialEq)]
 pub struct Input {
@@ -25,13 +20,15 @@ pub struct Input {
     pub origin: ModuleOrigin,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Analysed {
     pub ast: TypedModule,
     pub name: Vec<String>,
     pub origin: ModuleOrigin,
     pub type_info: typ::Module,
     pub source_base_path: PathBuf,
+    pub path: PathBuf,
+    pub src: String,
 }
 
 #[derive(Debug, PartialEq)]
@@ -40,6 +37,15 @@ pub struct OutputFile {
     pub path: PathBuf,
 }
 
+/// A file of the project that is copied into the output directory as it is,
+/// such as an image shown in the documentation.
+///
+#[derive(Debug, PartialEq)]
+pub struct CopiedFile {
+    pub from: PathBuf,
+    pub to: PathBuf,
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum ModuleOrigin {
     Src,
@@ -48,6 +54,9 @@ pub enum ModuleOrigin {
 }
 
 impl ModuleOrigin {
+    /// The directory within the output directory that the Erlang of a module
+    /// is written to when it is not within a named source or test directory.
+    ///
     pub fn dir_name(&self) -> &'static str {
         match self {
             ModuleOrigin::Src | ModuleOrigin::Dependency => "src",
@@ -65,71 +74,130 @@ pub struct Module {
     module: crate::ast::UntypedModule,
 }
 
-pub fn analysed(inputs: Vec<Input>) -> Result<Vec<Analysed>, Error> {
-    let module_count = inputs.len();
-    let mut source_tree = SourceTree::new(inputs)?;
-    let mut modules_type_infos = HashMap::new();
-    let mut compiled_modules = Vec::with_capacity(module_count);
+/// The modules analysed by earlier builds. Watch mode keeps one between
+/// builds so that only the modules that have changed, and the modules that
+/// import them, are analysed again.
+///
+#[derive(Debug, Default)]
+pub struct Cache {
+    modules: HashMap<String, Cached>,
+    /// Incremented each time a module is analysed, so that a module can tell
+    /// whether the modules it imports have been analysed again since.
+    version: u64,
+}
 
-    struct Out {
-        source_base_path: PathBuf,
-        name_string: String,
-        name: Vec<String>,
-        origin: ModuleOrigin,
-        ast: TypedModule,
-    }
+#[derive(Debug)]
+struct Cached {
+    analysed: Analysed,
+    version: u64,
+    /// The modules imported, and the versions of them the module was
+    /// analysed against.
+    imports: Vec<(String, Option<u64>)>,
+}
 
-    for Module {
-        src,
-        path,
-        module,
-        origin,
-        source_base_path,
-    } in source_tree.consume()?
-    {
-        let name = module.name.clone();
-        let name_string = module.name_string();
+impl Cache {
+    /// Type checks the modules, in an order where each module comes after
+    /// the modules it imports.
+    ///
+    pub fn analysed(&mut self, inputs: Vec<Input>) -> Result<Vec<Analysed>, Error> {
+        let module_count = inputs.len();
+        let mut source_tree = SourceTree::new(inputs)?;
+        let mut modules_type_infos = HashMap::new();
+        let mut compiled_modules = Vec::with_capacity(module_count);
 
-        println!("Compiling {}", name_string);
+        for Module {
+            src,
+            path,
+            module,
+            origin,
+            source_base_path,
+        } in source_tree.consume()?
+        {
+            let name = module.name.clone();
+            let name_string = module.name_string();
 
-        let ast = crate::typ::infer_module(module, &modules_type_infos)
-            .map_err(|error| Error::Type { path, src, error })?;
+            // Imported modules come first, so they are already in the cache
+            let imports: Vec<_> = module
+                .dependencies()
+                .into_iter()
+                .map(|(import, _)| {
+                    let version = self.modules.get(&import).map(|cached| cached.version);
+                    (import, version)
+                })
+                .collect();
+            let unchanged = self.modules.get(&name_string).filter(|cached| {
+                cached.analysed.src == src
+                    && cached.analysed.path == path
+                    && cached.analysed.origin == origin
+                    && cached.analysed.source_base_path == source_base_path
+                    && cached.imports == imports
+            });
+            if let Some(cached) = unchanged {
+                modules_type_infos.insert(name_string, cached.analysed.type_info.clone());
+                compiled_modules.push(cached.analysed.clone());
+                continue;
+            }
 
-        modules_type_infos.insert(name_string.clone(), ast.type_info.clone());
+            println!("Compiling {}", name_string);
 
-        compiled_modules.push(Out {
-            name,
-            name_string,
-            source_base_path,
-            origin,
-            ast,
-        });
-    }
+            let ast = crate::typ::infer_module(module, &modules_type_infos).map_err(|error| {
+                Error::Type {
+                    path: path.clone(),
+                    src: src.clone(),
+                    error,
+                }
+            })?;
 
-    Ok(compiled_modules
-        .into_iter()
-        .map(|out| {
-            let Out {
-                name,
-                source_base_path,
-                name_string,
-                origin,
-                ast,
-            } = out;
-            Analysed {
+            modules_type_infos.insert(name_string.clone(), ast.type_info.clone());
+
+            let analysed = Analysed {
+                type_info: ast.type_info.clone(),
                 ast,
                 name,
                 source_base_path,
+                path,
+                src,
                 origin,
-                type_info: modules_type_infos
-                    .remove(&name_string)
-                    .gleam_expect("project::compile(): Merging module type info"),
-            }
-        })
-        .collect())
+            };
+            self.version += 1;
+            self.modules.insert(
+                name_string,
+                Cached {
+                    analysed: analysed.clone(),
+                    version: self.version,
+                    imports,
+                },
+            );
+            compiled_modules.push(analysed);
+        }
+
+        // Modules that have been deleted are forgotten
+        self.modules
+            .retain(|name, _| modules_type_infos.contains_key(name));
+        Ok(compiled_modules)
+    }
 }
 
-pub fn generate_erlang(analysed: &[Analysed], files: &mut Vec<OutputFile>) {
+/// Generates Erlang for each module. Modules of the project are written to
+/// `output_dir`, and modules of dependencies to the `dependency_output_dir`
+/// directory of the package they belong to. Within these the Erlang is
+/// written to a directory with the name of the source or test directory that
+/// the module is in, e.g. `gen/itest` for a module in `itest`.
+///
+pub fn generate_erlang(
+    analysed: &[Analysed],
+    output_dir: &Path,
+    dependency_output_dir: &str,
+    files: &mut Vec<OutputFile>,
+) {
+    // Test modules generated from the code examples of source modules are in
+    // a source directory, but are written to `test` with the other tests
+    let src_dirs: HashSet<&Path> = analysed
+        .iter()
+        .filter(|module| module.origin != ModuleOrigin::Test)
+        .map(|module| module.source_base_path.as_path())
+        .collect();
+
     for Analysed {
         name,
         origin,
@@ -138,11 +206,24 @@ pub fn generate_erlang(analysed: &[Analysed], files: &mut Vec<OutputFile>) {
         ..
     } in analysed
     {
-        let gen_dir = source_base_path
-            .parent()
-            .unwrap()
-            .join("gen")
-            .join(origin.dir_name());
+        let dir_name = match source_base_path.file_name().and_then(|name| name.to_str()) {
+            Some(_)
+                if *origin == ModuleOrigin::Test
+                    && src_dirs.contains(source_base_path.as_path()) =>
+            {
+                origin.dir_name()
+            }
+            Some(name) => name,
+            None => origin.dir_name(),
+        };
+        let gen_dir = match origin {
+            ModuleOrigin::Src | ModuleOrigin::Test => output_dir.to_path_buf(),
+            ModuleOrigin::Dependency => source_base_path
+                .parent()
+                .unwrap()
+                .join(dependency_output_dir),
+        }
+        .join(dir_name);
         let erl_module_name = name.join("@");
 
         for (name, text) in crate::erl::records(&ast).into_iter() {
@@ -159,6 +240,47 @@ pub fn generate_erlang(analysed: &[Analysed], files: &mut Vec<OutputFile>) {
     }
 }
 
+/// Generates the `.app` file of the project into the `ebin` directory of
+/// `output_dir`, listing the application modules of the project. These are
+/// the modules generated from its source directories and the Erlang modules
+/// written by hand within them, such as the start module of an application.
+///
+pub fn generate_app_file(
+    config: &ProjectConfig,
+    root: &Path,
+    analysed: &[Analysed],
+    output_dir: &Path,
+    files: &mut Vec<OutputFile>,
+) {
+    let mut modules: Vec<String> = analysed
+        .iter()
+        .filter(|module| module.origin == ModuleOrigin::Src)
+        .map(|module| module.name.join("@"))
+        .collect();
+    modules.extend(erlang_modules(root, config));
+
+    files.push(OutputFile {
+        path: output_dir.join("ebin").join(format!("{}.app", config.name)),
+        text: crate::erl::application(config, &modules),
+    });
+}
+
+/// The names of the `.erl` modules in the source directories of the project.
+///
+fn erlang_modules(root: &Path, config: &ProjectConfig) -> Vec<String> {
+    let mut modules: Vec<String> = config
+        .src_dirs
+        .iter()
+        .flat_map(|dir| walkdir::WalkDir::new(root.join(dir)).into_iter())
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+        .filter(|entry| entry.path().extension().and_then(|e| e.to_str()) == Some("erl"))
+        .filter_map(|entry| Some(entry.path().file_stem()?.to_str()?.to_string()))
+        .collect();
+    modules.sort();
+    modules
+}
+
 pub fn collect_source(
     src_dir: PathBuf,
     origin: ModuleOrigin,
@@ -209,3 +331,170 @@ pub fn collect_source(
     }
     Ok(())
 }
+
+/// Output directories hold this file, listing the files written by the last
+/// build, so that only files written by the compiler are ever deleted.
+const OUTPUTS_FILE: &str = ".gleam_outputs";
+
+/// Writes the output files, and copies the copied files, into the output
+/// directory. Files whose contents have not changed are left untouched so
+/// that file watchers and mtime based caches are not invalidated, and files
+/// written by the previous build that are not part of the output (such as
+/// those of deleted modules) are removed. Any other files in the directory
+/// are left alone.
+///
+pub fn write_outputs(dir: &Path, files: &[OutputFile], copied: &[CopiedFile]) -> Result<(), Error> {
+    for file in files {
+        write_output(file)?;
+    }
+    for file in copied {
+        copy_output(file)?;
+    }
+
+    // Files outside of the directory, such as the Erlang of dependencies, are
+    // listed by their absolute path
+    let outputs: Vec<String> = files
+        .iter()
+        .map(|file| &file.path)
+        .chain(copied.iter().map(|file| &file.to))
+        .map(|path| match path.strip_prefix(dir) {
+            Ok(relative) => relative.to_path_buf(),
+            Err(_) => path.canonicalize().unwrap_or_else(|_| path.clone()),
+        })
+        .map(|path| path.to_string_lossy().to_string())
+        .collect();
+    let stale: Vec<PathBuf> = previous_outputs(dir)
+        .into_iter()
+        .filter(|path| !outputs.contains(path))
+        .map(|path| dir.join(path))
+        .collect();
+    for path in stale {
+        delete_output(&path)?;
+    }
+
+    write_output(&OutputFile {
+        path: dir.join(OUTPUTS_FILE),
+        text: outputs.join("\n"),
+    })
+}
+
+/// Deletes the files written to the output directory by the last build, and
+/// the directories that they leave empty.
+///
+pub fn delete_outputs(dir: &Path) -> Result<(), Error> {
+    for path in previous_outputs(dir) {
+        delete_output(&dir.join(path))?;
+    }
+    delete_output(&dir.join(OUTPUTS_FILE))
+}
+
+fn previous_outputs(dir: &Path) -> Vec<String> {
+    std::fs::read_to_string(dir.join(OUTPUTS_FILE))
+        .unwrap_or_default()
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| line.to_string())
+        .collect()
+}
+
+/// Deletes a file, and then each of the directories containing it that are
+/// left empty.
+///
+fn delete_output(path: &Path) -> Result<(), Error> {
+    if path.is_file() {
+        std::fs::remove_file(path).map_err(|e| Error::FileIO {
+            action: FileIOAction::Delete,
+            kind: FileKind::File,
+            path: path.to_path_buf(),
+            err: Some(e.to_string()),
+        })?;
+    }
+
+    for dir in path.ancestors().skip(1) {
+        let is_empty = std::fs::read_dir(dir)
+            .map(|mut entries| entries.next().is_none())
+            .unwrap_or(false);
+        if !is_empty {
+            break;
+        }
+        std::fs::remove_dir(dir).map_err(|e| Error::FileIO {
+            action: FileIOAction::Delete,
+            kind: FileKind::Directory,
+            path: dir.to_path_buf(),
+            err: Some(e.to_string()),
+        })?;
+    }
+    Ok(())
+}
+
+pub fn delete_dir(dir: &Path) -> Result<(), Error> {
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir).map_err(|e| Error::FileIO {
+            action: FileIOAction::Delete,
+            kind: FileKind::Directory,
+            path: dir.to_path_buf(),
+            err: Some(e.to_string()),
+        })?;
+    }
+    Ok(())
+}
+
+/// Writes a file if its contents have changed. The new contents are written
+/// to a temporary file which is then renamed over the old one, so the file
+/// is never seen half written.
+///
+pub fn write_output(file: &OutputFile) -> Result<(), Error> {
+    write_bytes(&file.path, file.text.as_bytes())
+}
+
+/// Copies a file if the copy is missing or its contents have changed.
+///
+fn copy_output(file: &CopiedFile) -> Result<(), Error> {
+    let bytes = std::fs::read(&file.from).map_err(|e| Error::FileIO {
+        action: FileIOAction::Read,
+        kind: FileKind::File,
+        path: file.from.clone(),
+        err: Some(e.to_string()),
+    })?;
+    write_bytes(&file.to, &bytes)
+}
+
+fn write_bytes(path: &Path, bytes: &[u8]) -> Result<(), Error> {
+    if std::fs::read(path).ok().as_deref() == Some(bytes) {
+        return Ok(());
+    }
+
+    let dir_path = path.parent().ok_or_else(|| Error::FileIO {
+        action: FileIOAction::FindParent,
+        kind: FileKind::Directory,
+        path: path.to_path_buf(),
+        err: None,
+    })?;
+
+    std::fs::create_dir_all(dir_path).map_err(|e| Error::FileIO {
+        action: FileIOAction::Create,
+        kind: FileKind::Directory,
+        path: dir_path.to_path_buf(),
+        err: Some(e.to_string()),
+    })?;
+
+    let file_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .gleam_expect("project::write_output(): file name");
+    let tmp_path = dir_path.join(format!(".{}.tmp", file_name));
+
+    std::fs::write(&tmp_path, bytes).map_err(|e| Error::FileIO {
+        action: FileIOAction::WriteTo,
+        kind: FileKind::File,
+        path: tmp_path.clone(),
+        err: Some(e.to_string()),
+    })?;
+
+    std::fs::rename(&tmp_path, path).map_err(|e| Error::FileIO {
+        action: FileIOAction::Rename,
+        kind: FileKind::File,
+        path: tmp_path.clone(),
+        err: Some(e.to_string()),
+    })
+}
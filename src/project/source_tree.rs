@@ -102,7 +102,9 @@ impl SourceTree {
                     location: location.clone(),
                 })?;
 
-                if module.origin == ModuleOrigin::Src
+                // Test modules are not included in builds of packages, so only
+                // other test modules may import them
+                if module.origin != ModuleOrigin::Test
                     && self
                         .modules
                         .get(&dep_index)
@@ -150,8 +152,14 @@ impl SourceTree {
                 error: e.map_token(|crate::grammar::Token(a, b)| (a, b.to_string())),
             })?;
 
-        // Annotate statements with their inline documentation
+        // Annotate statements with their inline documentation, and the module
+        // with its module comments
         attach_doc_comments(&mut module, &comments.doc_comments);
+        module.documentation = comments
+            .module_comments
+            .iter()
+            .map(|comment| comment.content.to_string())
+            .collect();
 
         // Store the name
         module.name = name.split('/').map(|s| s.to_string()).collect();
@@ -189,10 +197,18 @@ fn attach_doc_comments<'a, A, B, C>(
 ) {
     for statement in &mut module.statements {
         let location = statement.location();
-        // TODO: be more fine grained with how we apply the comments.
-        // i.e. Apply them to custom type constructors.
         let (doc, rest) = parser::take_before(comments, location.end);
         comments = rest;
         statement.put_doc(doc);
+
+        // The location of a custom type ends before its constructors, so the
+        // comments that follow are those of each constructor
+        if let crate::ast::Statement::CustomType { constructors, .. } = statement {
+            for constructor in constructors {
+                let (doc, rest) = parser::take_before(comments, constructor.location.start);
+                comments = rest;
+                constructor.put_doc(doc);
+            }
+        }
     }
 }
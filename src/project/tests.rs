@@ -12,6 +12,18 @@ fn compile_test() {
             input: vec![],
             expected: Ok(vec![]),
         },
+        Case {
+            input: vec![Input {
+                origin: ModuleOrigin::Dependency,
+                source_base_path: PathBuf::from("/my_lib/src"),
+                path: PathBuf::from("/my_lib/src/one.gleam"),
+                src: "".to_string(),
+            }],
+            expected: Ok(vec![OutputFile {
+                path: PathBuf::from("/my_lib/gen/src/one.erl"),
+                text: "-module(one).\n-compile(no_auto_import).\n\n\n".to_string(),
+            }]),
+        },
         Case {
             input: vec![
                 Input {
@@ -73,6 +85,29 @@ fn compile_test() {
                 test_module: "two".to_string(),
             }),
         },
+        Case {
+            input: vec![
+                Input {
+                    origin: ModuleOrigin::Test,
+                    source_base_path: PathBuf::from("/test"),
+                    path: PathBuf::from("/test/two.gleam"),
+                    src: "".to_string(),
+                },
+                Input {
+                    origin: ModuleOrigin::Dependency,
+                    source_base_path: PathBuf::from("/my_lib/src"),
+                    path: PathBuf::from("/my_lib/src/one.gleam"),
+                    src: "import two".to_string(),
+                },
+            ],
+            expected: Err(Error::SrcImportingTest {
+                path: PathBuf::from("/my_lib/src/one.gleam"),
+                src: "import two".to_string(),
+                location: crate::ast::SrcSpan { start: 7, end: 10 },
+                src_module: "one".to_string(),
+                test_module: "two".to_string(),
+            }),
+        },
         Case {
             input: vec![
                 Input {
@@ -664,14 +699,264 @@ get_name(Person) ->
                 },
             ]),
         },
+        // Erlang is written to a directory named after the source or test
+        // directory of the module, other than test modules generated from
+        // code examples, which are written to `test`
+        Case {
+            input: vec![
+                Input {
+                    origin: ModuleOrigin::Src,
+                    source_base_path: PathBuf::from("/src_gen"),
+                    path: PathBuf::from("/src_gen/one.gleam"),
+                    src: "".to_string(),
+                },
+                Input {
+                    origin: ModuleOrigin::Test,
+                    source_base_path: PathBuf::from("/itest"),
+                    path: PathBuf::from("/itest/two.gleam"),
+                    src: "".to_string(),
+                },
+                Input {
+                    origin: ModuleOrigin::Test,
+                    source_base_path: PathBuf::from("/src_gen"),
+                    path: PathBuf::from("/src_gen/one_doctest.gleam"),
+                    src: "".to_string(),
+                },
+            ],
+            expected: Ok(vec![
+                OutputFile {
+                    path: PathBuf::from("/gen/test/one_doctest.erl"),
+                    text: "-module(one_doctest).\n-compile(no_auto_import).\n\n\n".to_string(),
+                },
+                OutputFile {
+                    path: PathBuf::from("/gen/itest/two.erl"),
+                    text: "-module(two).\n-compile(no_auto_import).\n\n\n".to_string(),
+                },
+                OutputFile {
+                    path: PathBuf::from("/gen/src_gen/one.erl"),
+                    text: "-module(one).\n-compile(no_auto_import).\n\n\n".to_string(),
+                },
+            ]),
+        },
     ];
 
     for Case { input, expected } in cases.into_iter() {
-        let actual = analysed(input).map(|analysed| {
+        let actual = Cache::default().analysed(input).map(|analysed| {
             let mut output = Vec::with_capacity(analysed.len());
-            crate::project::generate_erlang(analysed.as_slice(), &mut output);
+            crate::project::generate_erlang(
+                analysed.as_slice(),
+                &PathBuf::from("/gen"),
+                "gen",
+                &mut output,
+            );
             output
         });
         assert_eq!(expected, actual);
     }
 }
+
+#[test]
+fn cache_test() {
+    let input = |name: &str, src: &str| Input {
+        source_base_path: PathBuf::from("/src"),
+        path: PathBuf::from(format!("/src/{}.gleam", name)),
+        src: src.to_string(),
+        origin: ModuleOrigin::Src,
+    };
+    let one = "pub fn go() { 1 }";
+    let two = "import one pub fn go() -> Int { one.go() }";
+    let three = "pub fn go() { 3 }";
+    let mut cache = Cache::default();
+    let version = |cache: &Cache, name: &str| cache.modules.get(name).map(|c| c.version);
+
+    let analysed = cache
+        .analysed(vec![
+            input("one", one),
+            input("two", two),
+            input("three", three),
+        ])
+        .unwrap();
+    assert_eq!(3, analysed.len());
+    assert_eq!(3, cache.version);
+    let three_version = version(&cache, "three");
+
+    // Unchanged modules are not analysed again
+    let again = cache
+        .analysed(vec![
+            input("one", one),
+            input("two", two),
+            input("three", three),
+        ])
+        .unwrap();
+    assert_eq!(analysed, again);
+    assert_eq!(3, cache.version);
+
+    // Changed modules are analysed again, along with the modules importing
+    // them, which are analysed after them
+    let _ = cache
+        .analysed(vec![
+            input("one", "pub fn go() { 2 }"),
+            input("two", two),
+            input("three", three),
+        ])
+        .unwrap();
+    assert_eq!(Some(4), version(&cache, "one"));
+    assert_eq!(Some(5), version(&cache, "two"));
+    assert_eq!(three_version, version(&cache, "three"));
+
+    // Deleted modules are forgotten, so the modules that imported them are
+    // checked against any changes made in the meantime when they return
+    let _ = cache
+        .analysed(vec![
+            input("one", "pub fn go() { \"one\" }"),
+            input("three", three),
+        ])
+        .unwrap();
+    assert_eq!(None, version(&cache, "two"));
+    let error = cache.analysed(vec![
+        input("one", "pub fn go() { \"one\" }"),
+        input("two", two),
+        input("three", three),
+    ]);
+    assert!(
+        matches!(error, Err(Error::Type { path, .. }) if path == PathBuf::from("/src/two.gleam"))
+    );
+}
+
+#[test]
+fn write_outputs_test() {
+    let dir = std::env::temp_dir().join("gleam_write_outputs_test");
+    let _ = std::fs::remove_dir_all(&dir);
+    let file = |path: &str, text: &str| OutputFile {
+        path: dir.join(path),
+        text: text.to_string(),
+    };
+
+    // Files that were not written by the compiler are never deleted
+    std::fs::create_dir_all(dir.join("src")).unwrap();
+    std::fs::write(dir.join("src/keep.erl"), "keep").unwrap();
+    std::fs::write(dir.join("data.txt"), "data").unwrap();
+
+    write_outputs(
+        &dir,
+        &[
+            file("src/one.erl", "one"),
+            file("src/two.erl", "two"),
+            file("src/nested/three.erl", "three"),
+        ],
+        &[CopiedFile {
+            from: dir.join("data.txt"),
+            to: dir.join("copied/data.txt"),
+        }],
+    )
+    .unwrap();
+    assert_eq!(
+        "data",
+        std::fs::read_to_string(dir.join("copied/data.txt")).unwrap()
+    );
+    let modified = |path: &str| {
+        std::fs::metadata(dir.join(path))
+            .unwrap()
+            .modified()
+            .unwrap()
+    };
+    let one_modified = modified("src/one.erl");
+
+    write_outputs(
+        &dir,
+        &[file("src/one.erl", "one"), file("src/two.erl", "two!")],
+        &[],
+    )
+    .unwrap();
+
+    // Unchanged files are not written again
+    assert_eq!(one_modified, modified("src/one.erl"));
+    assert_eq!(
+        "two!",
+        std::fs::read_to_string(dir.join("src/two.erl")).unwrap()
+    );
+
+    // Stale files and the directories they leave empty are deleted, but the
+    // originals of copied files are kept
+    assert!(!dir.join("src/nested").exists());
+    assert!(!dir.join("copied").exists());
+    assert!(dir.join("data.txt").is_file());
+
+    // No temporary files are left behind
+    let mut files: Vec<_> = std::fs::read_dir(dir.join("src"))
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect();
+    files.sort();
+    assert_eq!(vec!["keep.erl", "one.erl", "two.erl"], files);
+
+    // Deleting the outputs leaves only the other files
+    delete_outputs(&dir).unwrap();
+    assert!(!dir.join("src/one.erl").exists());
+    assert!(!dir.join(".gleam_outputs").exists());
+    assert!(dir.join("src/keep.erl").is_file());
+    assert!(dir.join("data.txt").is_file());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn delete_outputs_test() {
+    let root = std::env::temp_dir().join("gleam_delete_outputs_test");
+    let _ = std::fs::remove_dir_all(&root);
+    let dir = root.join("gen");
+    let dependency = root.join("_build/default/lib/wibble");
+    std::fs::create_dir_all(dependency.join("src")).unwrap();
+    std::fs::write(dependency.join("src/wibble.gleam"), "").unwrap();
+
+    // The Erlang of dependencies is written within their own directories
+    write_outputs(
+        &dir,
+        &[
+            OutputFile {
+                path: dir.join("src/app.erl"),
+                text: "app".to_string(),
+            },
+            OutputFile {
+                path: dependency.join("gen/src/wibble.erl"),
+                text: "wibble".to_string(),
+            },
+        ],
+        &[],
+    )
+    .unwrap();
+    assert!(dependency.join("gen/src/wibble.erl").is_file());
+
+    // And is deleted along with the project's
+    delete_outputs(&dir).unwrap();
+    assert!(!dir.exists());
+    assert!(!dependency.join("gen").exists());
+    assert!(dependency.join("src/wibble.gleam").is_file());
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn generate_app_file_test() {
+    let root = std::env::temp_dir().join("gleam_generate_app_file_test");
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(root.join("src/my_app")).unwrap();
+    std::fs::write(root.join("src/my_app_app.erl"), "").unwrap();
+    std::fs::write(root.join("src/my_app/ffi.erl"), "").unwrap();
+    std::fs::write(root.join("src/my_app.app.src"), "").unwrap();
+
+    let config = crate::config::parse(r#"name = "my_app""#, &root.join("gleam.toml")).unwrap();
+    let mut files = vec![];
+    generate_app_file(&config, &root, &[], &root.join("gen"), &mut files);
+
+    // Erlang modules written by hand are part of the application too
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].path, root.join("gen/ebin/my_app.app"));
+    assert!(
+        files[0].text.contains("{modules, [ffi, my_app_app]}"),
+        "{}",
+        files[0].text
+    );
+
+    let _ = std::fs::remove_dir_all(&root);
+}
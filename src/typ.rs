@@ -5,9 +5,9 @@ mod tests;
 use crate::ast::{
     self, Arg, ArgNames, BinOp, CallArg, Clause, ClauseGuard, Pattern, RecordConstructor, SrcSpan,
     Statement, TypeAst, TypedArg, TypedClause, TypedClauseGuard, TypedExpr, TypedModule,
-    TypedMultiPattern, TypedPattern, TypedStatement, UnqualifiedImport, UntypedArg, UntypedClause,
-    UntypedClauseGuard, UntypedExpr, UntypedModule, UntypedMultiPattern, UntypedPattern,
-    UntypedStatement,
+    TypedMultiPattern, TypedPattern, TypedStatement, UnaryOp, UnqualifiedImport, UntypedArg,
+    UntypedClause, UntypedClauseGuard, UntypedExpr, UntypedModule, UntypedMultiPattern,
+    UntypedPattern, UntypedStatement,
 };
 use crate::error::GleamExpect;
 use std::cell::RefCell;
@@ -1595,6 +1595,7 @@ This should not be possible. Please report this crash",
 
     Ok(ast::Module {
         name: module.name.clone(),
+        documentation: module.documentation,
         statements,
         type_info: Module {
             name: module.name,
@@ -1680,10 +1681,13 @@ pub fn infer(expr: UntypedExpr, level: usize, env: &mut Env) -> Result<TypedExpr
             location,
             pattern,
             value,
+            annotation,
             then,
             assert,
             ..
-        } => infer_let(pattern, *value, *then, assert, level, location, env),
+        } => infer_let(
+            pattern, *value, annotation, *then, assert, level, location, env,
+        ),
 
         UntypedExpr::Case {
             location,
@@ -1714,6 +1718,13 @@ pub fn infer(expr: UntypedExpr, level: usize, env: &mut Env) -> Result<TypedExpr
             ..
         } => infer_binop(name, *left, *right, level, location, env),
 
+        UntypedExpr::UnaryOp {
+            location,
+            name,
+            value,
+            ..
+        } => infer_unary_op(name, *value, level, location, env),
+
         UntypedExpr::FieldAccess {
             location,
             label,
@@ -2035,6 +2046,7 @@ fn infer_binop(
         BinOp::DivInt => (int(), int()),
         BinOp::DivFloat => (float(), float()),
         BinOp::ModuloInt => (int(), int()),
+        BinOp::Concatenate => (string(), string()),
     };
 
     let left = infer(left, level, env)?;
@@ -2052,9 +2064,34 @@ fn infer_binop(
     })
 }
 
+fn infer_unary_op(
+    name: UnaryOp,
+    value: UntypedExpr,
+    level: usize,
+    location: SrcSpan,
+    env: &mut Env,
+) -> Result<TypedExpr, Error> {
+    let typ = match name {
+        UnaryOp::NegateInt => int(),
+        UnaryOp::NegateFloat => float(),
+        UnaryOp::Not => bool(),
+    };
+
+    let value = infer(value, level, env)?;
+    unify(typ.clone(), value.typ(), env).map_err(|e| convert_unify_error(e, value.location()))?;
+
+    Ok(TypedExpr::UnaryOp {
+        location,
+        name,
+        typ,
+        value: Box::new(value),
+    })
+}
+
 fn infer_let(
     pattern: UntypedPattern,
     value: UntypedExpr,
+    annotation: Option<TypeAst>,
     then: UntypedExpr,
     assert: bool,
     level: usize,
@@ -2062,6 +2099,15 @@ fn infer_let(
     env: &mut Env,
 ) -> Result<TypedExpr, Error> {
     let value = infer(value, level + 1, env)?;
+
+    // Check that any type annotation is accurate. Type variables in the
+    // annotation are not generic, they stand for whatever type is inferred.
+    if let Some(ann) = &annotation {
+        let ann_typ = env.type_from_ast(ann, &mut hashmap![], NewTypeAction::MakeGeneric)?;
+        let ann_typ = instantiate(ann_typ, level + 1, &mut hashmap![], env);
+        unify(ann_typ, value.typ(), env).map_err(|e| convert_unify_error(e, value.location()))?;
+    }
+
     let value_typ = generalise(value.typ(), level + 1);
     let pattern = PatternTyper::new(env, level).unify(pattern, value_typ)?;
     let then = infer(then, level, env)?;
@@ -2070,6 +2116,7 @@ fn infer_let(
         location,
         typ,
         pattern,
+        annotation,
         value: Box::new(value),
         then: Box::new(then),
         assert: assert,
@@ -2430,6 +2477,179 @@ fn infer_clause_guard(
         ClauseGuard::Int {
             location, value, ..
         } => Ok(ClauseGuard::Int { location, value }),
+
+        ClauseGuard::Float {
+            location, value, ..
+        } => Ok(ClauseGuard::Float { location, value }),
+
+        ClauseGuard::String {
+            location, value, ..
+        } => Ok(ClauseGuard::String { location, value }),
+
+        ClauseGuard::Tuple {
+            location, elems, ..
+        } => {
+            let elems = elems
+                .into_iter()
+                .map(|elem| infer_clause_guard(elem, level, env))
+                .collect::<Result<_, _>>()?;
+            Ok(ClauseGuard::Tuple { location, elems })
+        }
+
+        ClauseGuard::TupleIndex {
+            location,
+            index,
+            tuple,
+            ..
+        } => {
+            let tuple = infer_clause_guard(*tuple, level, env)?;
+            match collapse_links(tuple.typ()).as_ref() {
+                Type::Tuple { elems } => {
+                    let typ = elems
+                        .get(index as usize)
+                        .ok_or_else(|| Error::OutOfBoundsTupleIndex {
+                            location: location.clone(),
+                            index,
+                            size: elems.len(),
+                        })?
+                        .clone();
+                    Ok(ClauseGuard::TupleIndex {
+                        location,
+                        index,
+                        typ,
+                        tuple: Box::new(tuple),
+                    })
+                }
+
+                typ if typ.is_unbound() => Err(Error::NotATupleUnbound {
+                    location: tuple.location().clone(),
+                }),
+
+                _ => Err(Error::NotATuple {
+                    location: tuple.location().clone(),
+                    given: tuple.typ(),
+                }),
+            }
+        }
+
+        ClauseGuard::FieldAccess {
+            location,
+            label,
+            container,
+            ..
+        } => {
+            let container = infer_clause_guard(*container, level, env)?;
+            let RecordAccessor { index, label, typ } = infer_known_record_access(
+                container.typ(),
+                container.location(),
+                label,
+                &location,
+                env,
+            )?;
+            Ok(ClauseGuard::FieldAccess {
+                location,
+                index: Some(index),
+                label,
+                typ,
+                container: Box::new(container),
+            })
+        }
+
+        ClauseGuard::Constructor {
+            location,
+            module,
+            name,
+            mut args,
+            ..
+        } => {
+            let cons = env
+                .get_value_constructor(module.as_ref(), &name)
+                .map_err(|e| convert_get_value_constructor_error(e, &location))?;
+
+            match cons.field_map() {
+                // The fun has a field map so labelled arguments may be present and need to be reordered.
+                Some(field_map) => field_map.reorder(&mut args, &location)?,
+
+                // The fun has no field map and so we error if arguments have been labelled
+                None => assert_no_labelled_arguments(&args)?,
+            }
+
+            // Only record constructors can be used in guards as the BEAM does
+            // not permit function calls there
+            match cons.variant {
+                ValueConstructorVariant::Record { .. } => (),
+                ValueConstructorVariant::LocalVariable
+                | ValueConstructorVariant::ModuleFn { .. } => crate::error::fatal_compiler_bug(
+                    "Unexpected value constructor type for a constructor guard.",
+                ),
+            };
+
+            let constructor_typ = cons.typ.clone();
+            let instantiated_constructor_type =
+                instantiate(constructor_typ, level, &mut hashmap![], env);
+            match &*instantiated_constructor_type {
+                Type::Fn {
+                    args: arg_types,
+                    retrn,
+                } => {
+                    if arg_types.len() != args.len() {
+                        return Err(Error::IncorrectArity {
+                            location,
+                            expected: arg_types.len(),
+                            given: args.len(),
+                        });
+                    }
+                    let args = args
+                        .into_iter()
+                        .zip(arg_types)
+                        .map(|(arg, typ)| {
+                            let CallArg {
+                                value,
+                                location,
+                                label,
+                            } = arg;
+                            let value = infer_clause_guard(value, level, env)?;
+                            unify(typ.clone(), value.typ(), env)
+                                .map_err(|e| convert_unify_error(e, value.location()))?;
+                            Ok(CallArg {
+                                value,
+                                location,
+                                label,
+                            })
+                        })
+                        .collect::<Result<_, _>>()?;
+                    Ok(ClauseGuard::Constructor {
+                        location,
+                        module,
+                        name,
+                        args,
+                        typ: retrn.clone(),
+                    })
+                }
+
+                Type::App { .. } => {
+                    if args.is_empty() {
+                        Ok(ClauseGuard::Constructor {
+                            location,
+                            module,
+                            name,
+                            args: vec![],
+                            typ: instantiated_constructor_type,
+                        })
+                    } else {
+                        Err(Error::IncorrectArity {
+                            location,
+                            expected: 0,
+                            given: args.len(),
+                        })
+                    }
+                }
+
+                _ => crate::error::fatal_compiler_bug(
+                    "Unexpected value constructor type for a constructor guard.",
+                ),
+            }
+        }
     }
 }
 
@@ -2485,23 +2705,45 @@ fn infer_record_access(
     // Infer the type of the (presumed) record
     let record = Box::new(infer(record, level, env)?);
 
+    let RecordAccessor { index, label, typ } =
+        infer_known_record_access(record.typ(), record.location(), label, &location, env)?;
+
+    Ok(TypedExpr::RecordAccess {
+        record,
+        label,
+        index,
+        location,
+        typ,
+    })
+}
+
+/// Find the accessor for a field of a record of an already inferred type,
+/// instantiating the field type for this particular record.
+///
+fn infer_known_record_access(
+    record_typ: Arc<Type>,
+    record_location: &SrcSpan,
+    label: String,
+    location: &SrcSpan,
+    env: &mut Env,
+) -> Result<RecordAccessor, Error> {
     // If we don't yet know the type of the record then we cannot use any accessors
-    if record.typ().is_unbound() {
+    if record_typ.is_unbound() {
         return Err(Error::RecordAccessUnknownType {
-            location: record.location().clone(),
+            location: record_location.clone(),
         });
     }
 
     // Error constructor helper function
     let unknown_field = |fields| Error::UnknownField {
-        typ: record.typ(),
+        typ: record_typ.clone(),
         location: location.clone(),
         label: label.clone(),
         fields,
     };
 
     // Check to see if it's a Type that can have accessible fields
-    let accessors = match collapse_links(record.typ().clone()).as_ref() {
+    let accessors = match collapse_links(record_typ.clone()).as_ref() {
         // A type in the current module which may have fields
         Type::App { module, name, .. } if module.as_slice() == env.current_module => {
             env.accessors.get(name)
@@ -2533,16 +2775,10 @@ fn infer_record_access(
     let mut type_vars = hashmap![];
     let accessor_record_type = instantiate(accessor_record_type, 0, &mut type_vars, env);
     let typ = instantiate(typ, 0, &mut type_vars, env);
-    unify(accessor_record_type, record.typ().clone(), env)
-        .map_err(|e| convert_unify_error(e, record.location()))?;
+    unify(accessor_record_type, record_typ, env)
+        .map_err(|e| convert_unify_error(e, record_location))?;
 
-    Ok(TypedExpr::RecordAccess {
-        record,
-        label,
-        index,
-        location,
-        typ,
-    })
+    Ok(RecordAccessor { index, label, typ })
 }
 
 struct PatternTyper<'a, 'b, 'c> {
@@ -2663,6 +2899,20 @@ impl<'a, 'b, 'c> PatternTyper<'a, 'b, 'c> {
                 Ok(Pattern::String { location, value })
             }
 
+            Pattern::StringPrefix {
+                location,
+                prefix,
+                rest,
+            } => {
+                unify(typ, string(), self.env).map_err(|e| convert_unify_error(e, &location))?;
+                let rest = Box::new(self.unify(*rest, string())?);
+                Ok(Pattern::StringPrefix {
+                    location,
+                    prefix,
+                    rest,
+                })
+            }
+
             Pattern::Nil { location } => {
                 unify(typ, list(self.env.new_unbound_var(self.level)), self.env)
                     .map_err(|e| convert_unify_error(e, &location))?;
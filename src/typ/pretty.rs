@@ -5,16 +5,30 @@ use std::sync::Arc;
 
 const INDENT: isize = 2;
 
-pub struct Printer {
+pub struct Printer<'a> {
     names: im::HashMap<usize, String>,
     uid: usize,
+    links: Option<&'a dyn Fn(&[String], &str) -> Option<String>>,
 }
 
-impl Printer {
+impl<'a> Printer<'a> {
     pub fn new() -> Self {
         Self {
             names: im::HashMap::new(),
             uid: 0,
+            links: None,
+        }
+    }
+
+    /// A printer that renders the names of type constructors as HTML links,
+    /// for use in documentation. `links` is given the module and name of
+    /// each type constructor and returns the URL to link to, if any.
+    ///
+    pub fn with_links(links: &'a dyn Fn(&[String], &str) -> Option<String>) -> Self {
+        Self {
+            names: im::HashMap::new(),
+            uid: 0,
+            links: Some(links),
         }
     }
 
@@ -34,13 +48,14 @@ impl Printer {
 
     pub fn to_doc(&mut self, typ: &Type) -> Document {
         match typ {
-            Type::App { name, args, .. } => {
+            Type::App {
+                module, name, args, ..
+            } => {
+                let name = self.type_name(module, name);
                 if args.is_empty() {
-                    name.clone().to_doc()
+                    name
                 } else {
-                    name.clone()
-                        .to_doc()
-                        .append("(")
+                    name.append("(")
                         .append(self.args_to_gleam_doc(args.as_slice()))
                         .append(")")
                 }
@@ -59,6 +74,15 @@ impl Printer {
         }
     }
 
+    fn type_name(&self, module: &[String], name: &str) -> Document {
+        match self.links.and_then(|links| links(module, name)) {
+            Some(url) => zero_width_text(format!("<a href=\"{}\">", url))
+                .append(name.to_string())
+                .append(zero_width_text("</a>".to_string())),
+            None => name.to_string().to_doc(),
+        }
+    }
+
     fn type_var_doc(&mut self, typ: &TypeVar) -> Document {
         match typ {
             TypeVar::Link { ref typ, .. } => self.to_doc(typ),
@@ -165,6 +165,7 @@ fn field_map_reorder_test() {
 fn infer_module_type_retention_test() {
     let module: UntypedModule = crate::ast::Module {
         name: vec!["ok".to_string()],
+        documentation: vec![],
         statements: vec![],
         type_info: (),
     };
@@ -206,10 +207,25 @@ fn infer_test() {
     assert_infer!("-2", "Int");
     assert_infer!("1.0", "Float");
     assert_infer!("-8.0", "Float");
+    assert_infer!("0xFF", "Int");
+    assert_infer!("-0o17", "Int");
+    assert_infer!("0b1010", "Int");
+    assert_infer!("1_000_000", "Int");
+    assert_infer!("1.5e-3", "Float");
+    assert_infer!("-1_000.0e10", "Float");
     assert_infer!("\"ok\"", "String");
     assert_infer!("\"ok\"", "String");
     assert_infer!("[]", "List(a)");
     assert_infer!("4 % 1", "Int");
+    assert_infer!("!True", "Bool");
+    assert_infer!("fn(x) { !x }", "fn(Bool) -> Bool");
+    assert_infer!("fn(x) { -x }", "fn(Int) -> Int");
+    assert_infer!("fn(x) { -.x }", "fn(Float) -> Float");
+    assert_infer!("fn(x, y) { !x && y }", "fn(Bool, Bool) -> Bool");
+    assert_infer!("fn(x) { -x * 2 }", "fn(Int) -> Int");
+    assert_infer!("fn(x) { 1 -x }", "fn(Int) -> Int");
+    assert_infer!("\"a\" <> \"b\"", "String");
+    assert_infer!("fn(x) { x <> \"!\" }", "fn(String) -> String");
     assert_infer!("4 > 1", "Bool");
     assert_infer!("4 >= 1", "Bool");
     assert_infer!("4 <= 1", "Bool");
@@ -323,7 +339,32 @@ fn infer_test() {
     // Multiple subject case
     assert_infer!("case 1, 2.0 { a, b -> a }", "Int");
     assert_infer!("case 1, 2.0 { a, b -> b }", "Float");
+
+    // Literals in clause guards
+    assert_infer!(r#"case "a" { x if x == "admin" -> 1 _ -> 0 }"#, "Int");
+    assert_infer!("case 1.0 { x if x >. 0.5 -> 1 _ -> 0 }", "Int");
+    assert_infer!(
+        "case tuple(1, 2.0) { x if x == tuple(1, 2.0) -> 1 _ -> 0 }",
+        "Int"
+    );
+    assert_infer!("case Ok(Nil) { x if x == Ok(Nil) -> 1 _ -> 0 }", "Int");
+    assert_infer!(
+        "case tuple(1, 2.0) { x if x.0 > 3 -> x.1 _ -> 0.0 }",
+        "Float"
+    );
     assert_infer!("case 1, 2.0, 3 { a, b, c -> a + c }", "Int");
+    assert_infer!("case 255 { 0xFF -> 1 0b1_0 -> 2 _ -> 0 }", "Int");
+    assert_infer!(
+        "case 1.0 { 1.0e3 -> 1 x if x >. 1.5e-3 -> 2 _ -> 0 }",
+        "Int"
+    );
+
+    // String prefix patterns
+    assert_infer!(r#"case "hello" { "he" <> rest -> rest _ -> "" }"#, "String");
+    assert_infer!(
+        r#"fn(x) { case x { "he" <> _ -> 1 _ -> 0 } }"#,
+        "fn(String) -> Int"
+    );
 
     // let
     assert_infer!("let [] = [] 1", "Int");
@@ -337,6 +378,13 @@ fn infer_test() {
     assert_infer!("let _ = 1 2.0", "Float");
     assert_infer!("let tuple(tag, x) = tuple(1.0, 1) x", "Int");
     assert_infer!("fn(x) { let tuple(a, b) = x a }", "fn(tuple(a, b)) -> a");
+    assert_infer!("let x: Int = 1 x", "Int");
+    assert_infer!("fn(x) { let y: Float = x y }", "fn(Float) -> Float");
+    assert_infer!("let xs: List(a) = [1] xs", "List(Int)");
+    assert_infer!(
+        "fn(x) { let y: tuple(a, b) = x y }",
+        "fn(tuple(a, b)) -> tuple(a, b)"
+    );
 
     // assert
     assert_infer!("assert [] = [] 1", "Int");
@@ -350,6 +398,11 @@ fn infer_test() {
     assert_infer!("assert _ = 1 2.0", "Float");
     assert_infer!("assert tuple(tag, x) = tuple(1.0, 1) x", "Int");
     assert_infer!("fn(x) { assert tuple(a, b) = x a }", "fn(tuple(a, b)) -> a");
+    assert_infer!("assert Ok(x): Result(a, String) = Ok(1) x", "Int");
+    assert_infer!(
+        "fn(r) { assert Ok(x): Result(Int, e) = r x }",
+        "fn(Result(Int, a)) -> Int"
+    );
 
     // Nil
     assert_infer!("Nil", "Nil");
@@ -397,6 +450,69 @@ fn infer_error_test() {
         },
     );
 
+    assert_error!(
+        "1 <> \"a\"",
+        Error::CouldNotUnify {
+            location: SrcSpan { start: 0, end: 1 },
+            expected: string(),
+            given: int(),
+        },
+    );
+
+    assert_error!(
+        r#"case 1 { "a" <> rest -> rest }"#,
+        Error::CouldNotUnify {
+            location: SrcSpan { start: 9, end: 20 },
+            expected: int(),
+            given: string(),
+        },
+    );
+
+    assert_error!(
+        "- 1.5",
+        Error::CouldNotUnify {
+            location: SrcSpan { start: 2, end: 5 },
+            expected: int(),
+            given: float(),
+        },
+    );
+
+    assert_error!(
+        "-.1",
+        Error::CouldNotUnify {
+            location: SrcSpan { start: 2, end: 3 },
+            expected: float(),
+            given: int(),
+        },
+    );
+
+    assert_error!(
+        "!1",
+        Error::CouldNotUnify {
+            location: SrcSpan { start: 1, end: 2 },
+            expected: bool(),
+            given: int(),
+        },
+    );
+
+    assert_error!(
+        "let x: Int = 1.0 x",
+        Error::CouldNotUnify {
+            location: SrcSpan { start: 13, end: 16 },
+            expected: int(),
+            given: float(),
+        },
+    );
+
+    assert_error!(
+        "assert Ok(x): Result(Int, String) = 1 x",
+        Error::CouldNotUnify {
+            location: SrcSpan { start: 36, end: 37 },
+            expected: result(int(), string()),
+            given: int(),
+        },
+    );
+
     assert_error!(
         "1 +. 1.0",
         Error::CouldNotUnify {
@@ -660,6 +776,24 @@ fn infer_error_test() {
         },
     );
 
+    assert_error!(
+        r#"case 1 { x if x == "1" -> 1 }"#,
+        Error::CouldNotUnify {
+            location: SrcSpan { start: 14, end: 22 },
+            expected: int(),
+            given: string(),
+        },
+    );
+
+    assert_error!(
+        "case tuple(1, 2) { x if x.2 == 1 -> 1 }",
+        Error::OutOfBoundsTupleIndex {
+            location: SrcSpan { start: 24, end: 27 },
+            index: 2,
+            size: 2,
+        },
+    );
+
     assert_error!(
         "let x = 1.0 case x { _ if x -> 1 }",
         Error::CouldNotUnify {
@@ -1239,6 +1373,23 @@ pub fn get_name(person: Person) { person.name }",
         ]
     );
 
+    // Record fields and constructors can be used in clause guards
+    assert_infer!(
+        "
+pub type Person { Person(name: String, age: Int) }
+pub fn is_admin(person: Person) {
+  case person {
+    _ if person.name == \"admin\" -> True
+    _ if person == Person(age: 1, name: \"Al\") -> True
+    _ -> False
+  }
+}",
+        vec![
+            ("Person", "fn(String, Int) -> Person"),
+            ("is_admin", "fn(Person) -> Bool"),
+        ]
+    );
+
     // We can access fields on custom types with only one record
     assert_infer!(
         "
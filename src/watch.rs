@@ -0,0 +1,122 @@
+#[cfg(test)]
+mod tests;
+
+use crate::config::{read_project_config, Profile};
+use crate::error::{Error, FileIOAction, FileKind};
+use notify::{DebouncedEvent, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver};
+use std::time::Duration;
+
+/// How long to wait for further changes before rebuilding, so that a burst of
+/// changes (such as checking out a branch) results in a single rebuild.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Runs the build, and then runs it again each time a Gleam source file or
+/// gleam.toml changes, until the process is stopped. Errors are printed
+/// rather than ending the process. The directories to watch are read from
+/// gleam.toml before each build so that changes to the config are picked up.
+///
+/// The build should keep a `project::Cache` from one run to the next, so that
+/// a rebuild only analyses the modules that have changed and those that
+/// import them.
+///
+pub fn run(
+    root: &str,
+    profile: Profile,
+    mut build: impl FnMut() -> Result<(), Error>,
+) -> Result<(), Error> {
+    loop {
+        // The watcher is started before building so that changes made during
+        // the build cause another build.
+        let (sender, receiver) = channel();
+        let mut watcher = notify::watcher(sender, DEBOUNCE).map_err(|e| watch_error(root, e))?;
+        for (path, mode) in watched_paths(root, profile) {
+            watcher
+                .watch(&path, mode)
+                .map_err(|e| watch_error(&path, e))?;
+        }
+
+        if let Err(e) = build() {
+            e.pretty_print();
+        }
+
+        println!("Watching for changes...");
+        if !wait_for_change(&receiver) {
+            println!("The file watcher stopped, restarting it");
+        }
+    }
+}
+
+/// The project root, to spot changes to gleam.toml, and the source
+/// directories of the project and its path dependencies.
+///
+fn watched_paths(root: &str, profile: Profile) -> Vec<(PathBuf, RecursiveMode)> {
+    let root_path = PathBuf::from(root);
+    let mut paths = vec![(root_path.clone(), RecursiveMode::NonRecursive)];
+
+    let config = match read_project_config(root) {
+        Ok(config) => config,
+        Err(_) => return paths,
+    };
+
+    let mut dirs: Vec<PathBuf> = config.src_dirs.iter().map(|d| root_path.join(d)).collect();
+    if config.include_tests(profile) {
+        dirs.extend(config.test_dirs.iter().map(|d| root_path.join(d)));
+    }
+    for package in crate::deps::path_packages(&root_path, &config).unwrap_or_default() {
+        paths.push((package.dir.clone(), RecursiveMode::NonRecursive));
+        dirs.extend(package.config.src_dirs.iter().map(|d| package.dir.join(d)));
+    }
+
+    paths.extend(
+        dirs.into_iter()
+            .filter(|dir| dir.is_dir())
+            .map(|dir| (dir, RecursiveMode::Recursive)),
+    );
+    paths
+}
+
+/// Blocks until a source file changes, and then until no more changes have
+/// been made for the debounce period. Returns whether a source file changed,
+/// rather than the watcher having stopped.
+///
+fn wait_for_change(receiver: &Receiver<DebouncedEvent>) -> bool {
+    loop {
+        match receiver.recv() {
+            Ok(event) if is_source_change(&event) => break,
+            Ok(_) => continue,
+            Err(_) => return false,
+        }
+    }
+    while receiver.recv_timeout(DEBOUNCE).is_ok() {}
+    true
+}
+
+fn is_source_change(event: &DebouncedEvent) -> bool {
+    match event {
+        DebouncedEvent::Create(path)
+        | DebouncedEvent::Write(path)
+        | DebouncedEvent::Remove(path) => is_source_path(path),
+        DebouncedEvent::Rename(from, to) => is_source_path(from) || is_source_path(to),
+        DebouncedEvent::Rescan => true,
+        DebouncedEvent::NoticeWrite(_)
+        | DebouncedEvent::NoticeRemove(_)
+        | DebouncedEvent::Chmod(_)
+        | DebouncedEvent::Error(_, _) => false,
+    }
+}
+
+fn is_source_path(path: &Path) -> bool {
+    path.extension().and_then(|e| e.to_str()) == Some("gleam")
+        || path.file_name().and_then(|n| n.to_str()) == Some("gleam.toml")
+}
+
+fn watch_error(path: impl AsRef<Path>, error: notify::Error) -> Error {
+    Error::FileIO {
+        action: FileIOAction::Watch,
+        kind: FileKind::Directory,
+        path: path.as_ref().to_path_buf(),
+        err: Some(error.to_string()),
+    }
+}
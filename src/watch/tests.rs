@@ -0,0 +1,87 @@
+use super::*;
+use std::sync::mpsc::TryRecvError;
+use std::thread;
+
+#[test]
+fn is_source_change_test() {
+    let path = |p: &str| PathBuf::from(p);
+
+    assert!(is_source_change(&DebouncedEvent::Write(path(
+        "src/one.gleam"
+    ))));
+    assert!(is_source_change(&DebouncedEvent::Create(path(
+        "src/one/two.gleam"
+    ))));
+    assert!(is_source_change(&DebouncedEvent::Remove(path(
+        "test/one_test.gleam"
+    ))));
+    assert!(is_source_change(&DebouncedEvent::Write(path("gleam.toml"))));
+    assert!(is_source_change(&DebouncedEvent::Rescan));
+
+    // Renames count if either side is a source file
+    assert!(is_source_change(&DebouncedEvent::Rename(
+        path("src/one.gleam"),
+        path("src/one.gleam~")
+    )));
+    assert!(is_source_change(&DebouncedEvent::Rename(
+        path("src/.one.gleam.swp"),
+        path("src/one.gleam")
+    )));
+    assert!(!is_source_change(&DebouncedEvent::Rename(
+        path("src/one.txt"),
+        path("src/two.txt")
+    )));
+
+    // Other files, and events that do not change contents, are ignored
+    assert!(!is_source_change(&DebouncedEvent::Write(path(
+        "src/one.erl"
+    ))));
+    assert!(!is_source_change(&DebouncedEvent::Write(path(
+        "src/gleam.toml.bak"
+    ))));
+    assert!(!is_source_change(&DebouncedEvent::Write(path(
+        "gleam.lock"
+    ))));
+    assert!(!is_source_change(&DebouncedEvent::Chmod(path(
+        "src/one.gleam"
+    ))));
+    assert!(!is_source_change(&DebouncedEvent::NoticeWrite(path(
+        "src/one.gleam"
+    ))));
+    assert!(!is_source_change(&DebouncedEvent::NoticeRemove(path(
+        "src/one.gleam"
+    ))));
+}
+
+#[test]
+fn wait_for_change_test() {
+    let event = |p: &str| DebouncedEvent::Write(PathBuf::from(p));
+
+    // Changes to other files do not end the wait
+    let (sender, receiver) = channel();
+    sender.send(event("src/one.erl")).unwrap();
+    sender.send(event("README.md")).unwrap();
+    drop(sender);
+    assert!(!wait_for_change(&receiver));
+
+    // A burst of changes is waited out, and results in a single change
+    let (sender, receiver) = channel();
+    sender.send(event("src/one.erl")).unwrap();
+    sender.send(event("src/one.gleam")).unwrap();
+    let burst = thread::spawn(move || {
+        for _ in 0..3 {
+            thread::sleep(DEBOUNCE / 2);
+            sender.send(event("src/two.gleam")).unwrap();
+        }
+        thread::sleep(DEBOUNCE * 3);
+        sender.send(event("src/three.gleam")).unwrap();
+        sender
+    });
+    assert!(wait_for_change(&receiver));
+    assert_eq!(Err(TryRecvError::Empty), receiver.try_recv());
+
+    // Changes made after the burst are left for the next wait
+    let sender = burst.join().unwrap();
+    assert_eq!(Ok(event("src/three.gleam")), receiver.try_recv());
+    drop(sender);
+}
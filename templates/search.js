@@ -0,0 +1,86 @@
+// Searches the modules, types, constructors, and functions of the package.
+// The index is loaded from search_index.js by the page, so no requests are
+// made and the docs can be searched offline.
+(function () {
+  var maxResults = 20;
+  var input = document.getElementById("search-input");
+  var results = document.getElementById("search-results");
+  if (!input || !results || !window.searchIndex) {
+    return;
+  }
+  var unnest = input.getAttribute("data-unnest");
+
+  // Lower numbers are better matches. Items not containing every term of
+  // the query do not match at all.
+  function score(item, terms) {
+    var name = item.name.toLowerCase();
+    var text = [item.module, item.name, item.signature, item.doc]
+      .join(" ")
+      .toLowerCase();
+    for (var i = 0; i < terms.length; i++) {
+      if (text.indexOf(terms[i]) === -1) {
+        return null;
+      }
+    }
+    var first = terms[0];
+    if (name === first) return 0;
+    if (name.indexOf(first) === 0) return 1;
+    if (name.indexOf(first) !== -1) return 2;
+    return 3;
+  }
+
+  function search(query) {
+    var terms = query.toLowerCase().split(/\s+/).filter(Boolean);
+    if (terms.length === 0) {
+      return [];
+    }
+    var matches = [];
+    window.searchIndex.forEach(function (item) {
+      var s = score(item, terms);
+      if (s !== null) {
+        matches.push({ score: s, item: item });
+      }
+    });
+    matches.sort(function (a, b) {
+      return a.score - b.score || a.item.name.length - b.item.name.length;
+    });
+    return matches.slice(0, maxResults).map(function (match) {
+      return match.item;
+    });
+  }
+
+  function element(tag, className, text) {
+    var el = document.createElement(tag);
+    el.className = className;
+    el.textContent = text;
+    return el;
+  }
+
+  function render(items) {
+    results.innerHTML = "";
+    items.forEach(function (item) {
+      var link = document.createElement("a");
+      link.href = unnest + "/" + item.url;
+      link.appendChild(element("span", "search-result-kind", item.kind));
+      link.appendChild(
+        element(
+          "span",
+          "search-result-name",
+          item.kind === "module" ? item.name : item.module + "." + item.name
+        )
+      );
+      if (item.doc) {
+        link.appendChild(element("span", "search-result-doc", item.doc));
+      }
+      link.title = item.signature;
+      var li = document.createElement("li");
+      li.appendChild(link);
+      results.appendChild(li);
+    });
+    results.hidden = items.length === 0;
+  }
+
+  input.addEventListener("input", function () {
+    render(search(input.value));
+  });
+})();